@@ -0,0 +1,366 @@
+use std::{
+    fmt::Display,
+    io::{self, Write},
+    path::Path,
+    process::Command,
+};
+
+use serde::Deserialize;
+
+use crate::{app::AppResult, key::KeyChord, project::Project};
+
+/// What an action does to the selected project.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum Operation {
+    /// Outputs the path of the project, so that the shell changes directory to it.
+    Cd,
+    /// Copies the path of the project to the clipboard.
+    CopyPath,
+    /// Opens the URL of the project's git remote in the browser.
+    OpenRemote,
+    /// Runs a command template with the shell.
+    ///
+    /// `{path}`, `{name}` and `{kind}` are replaced by the project's path, directory name and
+    /// kind.
+    Command(String),
+}
+
+/// A named operation to run on the selected project.
+#[derive(Clone, Debug, PartialEq, Eq, Deserialize)]
+#[serde(from = "ActionConfig")]
+pub struct Action {
+    /// Name of the action, from its key in the configuration.
+    pub name: String,
+    /// Short description of the action, displayed in the action menu.
+    pub description: Option<String>,
+    pub operation: Operation,
+    /// Key triggering the action.
+    pub key: Option<KeyChord>,
+}
+
+/// An action as written in the configuration file.
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+struct ActionConfig {
+    command: String,
+    description: Option<String>,
+    key: Option<KeyChord>,
+}
+
+impl From<ActionConfig> for Action {
+    fn from(config: ActionConfig) -> Self {
+        Self {
+            name: String::new(),
+            description: config.description,
+            operation: Operation::Command(config.command),
+            key: config.key,
+        }
+    }
+}
+
+impl Action {
+    fn builtin(name: &str, description: &str, operation: Operation) -> Self {
+        Self {
+            name: name.to_string(),
+            description: Some(description.to_string()),
+            operation,
+            key: None,
+        }
+    }
+
+    /// Returns the actions available without any configuration.
+    pub fn builtins() -> Vec<Self> {
+        let editor = if cfg!(windows) {
+            "notepad {path}"
+        } else {
+            "${VISUAL:-${EDITOR:-vi}} {path}"
+        };
+
+        vec![
+            Self::builtin("cd", "Change directory", Operation::Cd),
+            Self::builtin(
+                "edit",
+                "Open in editor",
+                Operation::Command(editor.to_string()),
+            ),
+            Self::builtin("copy-path", "Copy path", Operation::CopyPath),
+            Self::builtin("open-remote", "Open git remote URL", Operation::OpenRemote),
+            Self::builtin(
+                "reveal",
+                "Reveal in file manager",
+                Operation::Command(format!("{} {{path}}", opener())),
+            ),
+        ]
+    }
+
+    /// Returns the description of the action, or its command if it has none.
+    pub fn summary(&self) -> &str {
+        match (&self.description, &self.operation) {
+            (Some(description), _) => description,
+            (None, Operation::Command(command)) => command,
+            (None, _) => "",
+        }
+    }
+
+    /// Returns whether the action can be run on the given project.
+    pub fn is_available(&self, project: &Project) -> bool {
+        match self.operation {
+            Operation::OpenRemote => project.path.join(".git").exists(),
+            _ => true,
+        }
+    }
+
+    /// Replaces the placeholders of a command template with the values of the given project.
+    ///
+    /// Values are quoted so that they can be safely passed to the shell. The template is read once
+    /// from left to right, so that placeholders appearing in the values are not replaced.
+    pub fn render(command: &str, project: &Project) -> String {
+        let placeholders = [
+            ("{path}", project.path.to_string_lossy().into_owned()),
+            ("{name}", project.name()),
+            ("{kind}", project.kind.to_string()),
+        ];
+        let mut rendered = String::with_capacity(command.len());
+        let mut rest = command;
+
+        while let Some(start) = rest.find('{') {
+            rendered.push_str(&rest[..start]);
+            rest = &rest[start..];
+
+            match placeholders
+                .iter()
+                .find(|(placeholder, _)| rest.starts_with(placeholder))
+            {
+                Some((placeholder, value)) => {
+                    rendered.push_str(&quote(value));
+                    rest = &rest[placeholder.len()..];
+                }
+                None => {
+                    rendered.push('{');
+                    rest = &rest[1..];
+                }
+            }
+        }
+
+        rendered.push_str(rest);
+        rendered
+    }
+
+    /// Runs the action on the given project and waits for it to finish.
+    ///
+    /// The project path is written to `output` when changing directory.
+    pub fn run(&self, project: &Project, output: &mut dyn Write) -> AppResult<()> {
+        match &self.operation {
+            Operation::Cd => {
+                write!(output, "{}", project.path.display())?;
+                output.flush()?;
+                Ok(())
+            }
+            Operation::CopyPath => {
+                let path = project.path.to_string_lossy();
+                // OSC 52, supported by most terminals and forwarded through ssh
+                write!(io::stderr(), "\x1b]52;c;{}\x07", base64(path.as_bytes()))?;
+                Ok(())
+            }
+            Operation::OpenRemote => {
+                let url = remote_url(&project.path)?;
+                self.shell(&format!("{} {}", opener(), quote(&url)))
+            }
+            Operation::Command(command) => self.shell(&Self::render(command, project)),
+        }
+    }
+
+    /// Runs a command with the platform's shell.
+    fn shell(&self, command: &str) -> AppResult<()> {
+        let status = if cfg!(windows) {
+            Command::new("cmd").arg("/C").arg(command).status()
+        } else {
+            Command::new("sh").arg("-c").arg(command).status()
+        }
+        .map_err(|err| format!("failed to run action '{}': {err}", self.name))?;
+
+        if !status.success() {
+            return Err(format!("action '{}' failed: {status}", self.name).into());
+        }
+
+        Ok(())
+    }
+}
+
+impl Display for Action {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(&self.name)
+    }
+}
+
+/// Returns the command opening files and URLs with the default application.
+fn opener() -> &'static str {
+    if cfg!(windows) {
+        "explorer"
+    } else if cfg!(target_os = "macos") {
+        "open"
+    } else {
+        "xdg-open"
+    }
+}
+
+/// Quotes a value for the platform's shell.
+fn quote(value: &str) -> String {
+    if cfg!(windows) {
+        format!("\"{value}\"")
+    } else {
+        format!("'{}'", value.replace('\'', r"'\''"))
+    }
+}
+
+/// Returns the web URL of the `origin` remote of the git repository at `path`.
+fn remote_url(path: &Path) -> AppResult<String> {
+    let output = Command::new("git")
+        .arg("-C")
+        .arg(path)
+        .args(["remote", "get-url", "origin"])
+        .output()?;
+
+    if !output.status.success() {
+        return Err(format!("'{}' has no 'origin' remote", path.display()).into());
+    }
+
+    Ok(web_url(String::from_utf8_lossy(&output.stdout).trim()))
+}
+
+/// Converts a git remote URL to the URL of its web page.
+///
+/// `git@github.com:user/repo.git` becomes `https://github.com/user/repo`.
+fn web_url(remote: &str) -> String {
+    let remote = remote.strip_suffix(".git").unwrap_or(remote);
+
+    if let Some(rest) = remote.strip_prefix("ssh://") {
+        let rest = rest.split_once('@').map_or(rest, |(_, host)| host);
+        return format!("https://{rest}");
+    }
+
+    if !remote.contains("://") {
+        if let Some((host, path)) = remote.split_once(':') {
+            let host = host.split_once('@').map_or(host, |(_, host)| host);
+            return format!("https://{host}/{path}");
+        }
+    }
+
+    remote.to_string()
+}
+
+/// Encodes bytes in standard base64, with padding.
+fn base64(bytes: &[u8]) -> String {
+    const ALPHABET: &[u8; 64] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789+/";
+
+    let mut encoded = String::with_capacity(bytes.len().div_ceil(3) * 4);
+
+    for chunk in bytes.chunks(3) {
+        let b = [
+            chunk[0],
+            *chunk.get(1).unwrap_or(&0),
+            *chunk.get(2).unwrap_or(&0),
+        ];
+        let n = (b[0] as u32) << 16 | (b[1] as u32) << 8 | b[2] as u32;
+
+        for i in 0..4 {
+            if i <= chunk.len() {
+                encoded.push(ALPHABET[(n >> (18 - 6 * i) & 0x3f) as usize] as char);
+            } else {
+                encoded.push('=');
+            }
+        }
+    }
+
+    encoded
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn web_url_from_scp() {
+        assert_eq!(
+            web_url("git@github.com:Valentin271/fp.git"),
+            "https://github.com/Valentin271/fp"
+        );
+    }
+
+    #[test]
+    fn web_url_from_ssh() {
+        assert_eq!(
+            web_url("ssh://git@gitlab.com/group/project.git"),
+            "https://gitlab.com/group/project"
+        );
+    }
+
+    #[test]
+    fn web_url_from_https() {
+        assert_eq!(
+            web_url("https://github.com/Valentin271/fp.git"),
+            "https://github.com/Valentin271/fp"
+        );
+        assert_eq!(
+            web_url("https://github.com/Valentin271/fp"),
+            "https://github.com/Valentin271/fp"
+        );
+    }
+
+    #[test]
+    fn base64_padding() {
+        assert_eq!(base64(b""), "");
+        assert_eq!(base64(b"f"), "Zg==");
+        assert_eq!(base64(b"fo"), "Zm8=");
+        assert_eq!(base64(b"foo"), "Zm9v");
+        assert_eq!(base64(b"/home/me"), "L2hvbWUvbWU=");
+    }
+
+    #[cfg(unix)]
+    mod render {
+        use super::*;
+        use crate::project::ProjectKind;
+
+        fn project(path: &str) -> Project {
+            Project {
+                kind: ProjectKind::Rust,
+                ..Project::test(path)
+            }
+        }
+
+        #[test]
+        fn placeholders() {
+            assert_eq!(
+                Action::render(
+                    "tmux new-session -s {name} -c {path} # {kind}",
+                    &project("/home/me/fp")
+                ),
+                "tmux new-session -s 'fp' -c '/home/me/fp' # 'rust'"
+            );
+        }
+
+        #[test]
+        fn quotes_values() {
+            assert_eq!(
+                Action::render("nvim {path}", &project("/home/me/it's a project")),
+                r"nvim '/home/me/it'\''s a project'"
+            );
+        }
+
+        #[test]
+        fn placeholders_in_values() {
+            assert_eq!(
+                Action::render("cd {path} && echo {name}", &project("/tmp/{name}/it's")),
+                r"cd '/tmp/{name}/it'\''s' && echo 'it'\''s'"
+            );
+        }
+
+        #[test]
+        fn without_placeholders() {
+            assert_eq!(
+                Action::render("echo hello", &project("/home/me/fp")),
+                "echo hello"
+            );
+        }
+    }
+}
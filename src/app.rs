@@ -1,17 +1,33 @@
-use std::{error, time::Duration};
+use std::{
+    cmp::Ordering,
+    collections::{HashMap, HashSet},
+    error,
+    path::{Path, PathBuf},
+    time::{Duration, Instant},
+};
 
 use ratatui::{prelude::*, widgets::*};
-use state::AppState;
+pub use state::{AppState, Mode, EXIT_CANCELLED};
 
 use crate::{
-    project::Project,
+    action::Action,
+    history::History,
+    key::KeyChord,
+    keymap::{Command, Keymap},
+    layout::{Layout, ListOrder},
+    pins::Pins,
+    project::{Project, SortOrder},
     sorter::Sorter,
+    theme::{set_theme, theme, Theme},
     ui::projects_list,
-    widgets::{Preview, Searchbar},
+    widgets::{ActionMenu, Help, Preview, Searchbar},
 };
 
 mod state;
 
+/// Maximum delay between two clicks for them to be a double click.
+const DOUBLE_CLICK_DELAY: Duration = Duration::from_millis(400);
+
 /// Application result type.
 pub type AppResult<T> = std::result::Result<T, Box<dyn error::Error>>;
 
@@ -19,16 +35,55 @@ pub type AppResult<T> = std::result::Result<T, Box<dyn error::Error>>;
 pub struct App {
     /// Is the application running?
     pub state: AppState,
-    /// The time the app took to startup
+    /// The time the project discovery took
     pub start_time: Duration,
+    /// When the project discovery started, while it is running
+    scan_start: Option<Instant>,
+    /// Every project found, sorted by path
+    discovered: Vec<Project>,
+    /// Closest parent project of each nested project
+    parents: HashMap<PathBuf, PathBuf>,
+    /// Whether projects nested in another project are listed
+    nested: bool,
     /// List of projects
     pub projects: Vec<Project>,
     /// List of projects filtered
     pub filtered_projects: Vec<Project>,
     /// UI list state
     pub list_state: ListState,
+    /// Actions that can be run on the selected project
+    pub actions: Vec<Action>,
+    /// Key bindings
+    pub keymap: Keymap,
+    /// Layout of the interface
+    pub layout: Layout,
+    /// Key waiting for the next one to form a normal mode command, like the first `g` of `gg`
+    pending_key: Option<KeyChord>,
+    /// Pinned projects, listed first
+    pub pins: Pins,
+    /// Frecency of the projects, if enabled
+    pub history: Option<History>,
+    /// Paths of the marked projects, kept across searches
+    marked: HashSet<PathBuf>,
+    /// Name of the built-in theme being previewed, if any
+    previewed_theme: Option<&'static str>,
+    /// Message displayed in the search bar instead of the help hint, like the previewed theme
+    notice: Option<String>,
+    /// Order of the project list when nothing is searched
+    sort_order: SortOrder,
+    /// Whether vim-style modal editing is enabled
+    vim: bool,
+    mode: Mode,
+    /// Area of the project list, recorded during render
+    list_area: Rect,
+    /// Area of the preview, recorded during render, empty if hidden
+    preview_area: Rect,
+    /// Time and index of the last click on the project list
+    last_click: Option<(Instant, usize)>,
     searchbar: Searchbar,
     preview: Preview,
+    action_menu: ActionMenu,
+    help: Help,
     sorter: Sorter,
 }
 
@@ -37,11 +92,32 @@ impl Default for App {
         Self {
             state: Default::default(),
             start_time: Duration::default(),
+            scan_start: None,
+            discovered: Vec::new(),
+            parents: HashMap::new(),
+            nested: false,
             projects: Vec::new(),
             filtered_projects: Vec::new(),
             list_state: ListState::default().with_selected(Some(0)),
+            actions: Vec::new(),
+            keymap: Keymap::default(),
+            layout: Layout::default(),
+            pending_key: None,
+            pins: Pins::default(),
+            history: None,
+            marked: HashSet::new(),
+            previewed_theme: None,
+            notice: None,
+            sort_order: SortOrder::default(),
+            vim: false,
+            mode: Mode::default(),
+            list_area: Rect::default(),
+            preview_area: Rect::default(),
+            last_click: None,
             searchbar: Searchbar::default(),
             preview: Preview::default(),
+            action_menu: ActionMenu::default(),
+            help: Help::default(),
             sorter: Sorter::default(),
         }
     }
@@ -53,117 +129,692 @@ impl App {
     where
         I: IntoIterator<Item = Project>,
     {
-        let mut projects: Vec<Project> = projects.into_iter().collect();
-        projects.sort();
-
         let mut app = Self {
-            projects,
+            discovered: projects.into_iter().collect(),
             ..Default::default()
         };
 
-        app.dedup();
+        app.sort_projects();
         app.filtered_projects.clone_from(&app.projects);
         app.preview.select(app.selected().cloned());
         app
     }
 
-    /// Remove duplicates and subprojects
-    fn dedup(&mut self) {
-        self.projects.dedup();
+    /// Marks the project discovery as running, projects being added as they are found.
+    pub fn start_scan(&mut self) {
+        self.scan_start = Some(Instant::now());
+    }
 
-        let projects = self.projects.clone();
+    /// Marks the project discovery as finished, recording how long it took.
+    pub fn finish_scan(&mut self) {
+        if let Some(start) = self.scan_start.take() {
+            self.start_time = start.elapsed();
+        }
+    }
+
+    pub fn is_scanning(&self) -> bool {
+        self.scan_start.is_some()
+    }
+
+    /// Returns how long the project discovery took, or has been running for.
+    pub fn scan_duration(&self) -> Duration {
+        self.scan_start
+            .map_or(self.start_time, |start| start.elapsed())
+    }
+
+    /// Adds projects found by the discovery, filtering the list again.
+    ///
+    /// The highlighted project stays highlighted if it still matches the search.
+    pub fn add_projects(&mut self, mut projects: Vec<Project>) {
+        if projects.is_empty() {
+            return;
+        }
+
+        let selected = self.selected().map(|project| project.path.clone());
+
+        projects.sort();
+        dedup(&mut projects);
 
-        self.projects.retain(|p| {
-            for p2 in &projects {
-                if p.path.starts_with(&p2.path) && p != p2 {
-                    return false;
+        let mut added = Vec::new();
+        let mut changed = HashSet::new();
+        for project in &projects {
+            match self.position(&project.path) {
+                None => added.push(project.path.clone()),
+                Some(index) if self.discovered[index].workspace.is_none() => {
+                    if project.workspace.is_some() {
+                        changed.insert(project.path.clone());
+                    }
                 }
+                Some(_) => {}
+            }
+        }
+
+        self.discovered = merge(std::mem::take(&mut self.discovered), projects, Ord::cmp);
+        dedup(&mut self.discovered);
+
+        for path in added {
+            changed.extend(self.nest(&path));
+            changed.insert(path);
+        }
+        self.relist(&changed);
+
+        self.refilter();
+        self.select_path(selected.as_deref());
+    }
+
+    /// Returns the index of the discovered project at the given path.
+    fn position(&self, path: &Path) -> Option<usize> {
+        self.discovered
+            .binary_search_by(|project| project.path.as_path().cmp(path))
+            .ok()
+    }
+
+    /// Finds the parent of a newly discovered project, and makes it the parent of the projects it
+    /// contains when it is closer than their current one.
+    ///
+    /// Returns the paths of the projects whose parent changed.
+    fn nest(&mut self, path: &Path) -> Vec<PathBuf> {
+        let parent = path
+            .ancestors()
+            .skip(1)
+            .find(|ancestor| self.position(ancestor).is_some());
+        if let Some(parent) = parent {
+            self.parents
+                .insert(path.to_path_buf(), parent.to_path_buf());
+        }
+
+        let Some(index) = self.position(path) else {
+            return Vec::new();
+        };
+
+        let mut nested = Vec::new();
+        for project in self.discovered[index + 1..]
+            .iter()
+            .take_while(|project| project.path.starts_with(path))
+        {
+            let closer = self
+                .parents
+                .get(&project.path)
+                .is_none_or(|parent| path.starts_with(parent));
+
+            if closer {
+                self.parents
+                    .insert(project.path.clone(), path.to_path_buf());
+                nested.push(project.path.clone());
             }
-            true
+        }
+        nested
+    }
+
+    /// Lists or hides the given discovered projects again, keeping the list sorted.
+    fn relist(&mut self, paths: &HashSet<PathBuf>) {
+        self.projects
+            .retain(|project| !paths.contains(&project.path));
+
+        let mut listed: Vec<_> = self
+            .discovered
+            .iter()
+            .filter(|project| paths.contains(&project.path) && self.is_listed(project))
+            .cloned()
+            .collect();
+        self.sort_order.sort(&mut listed);
+
+        let order = self.sort_order;
+        self.projects = merge(std::mem::take(&mut self.projects), listed, |a, b| {
+            order.compare(a, b)
         });
     }
 
+    /// Returns whether the discovered project is listed, nested projects being only listed in
+    /// nested mode unless they are workspace members.
+    fn is_listed(&self, project: &Project) -> bool {
+        self.nested || project.workspace.is_some() || !self.parents.contains_key(&project.path)
+    }
+
+    /// Selects the project at the given path, or the first one if it is not listed.
+    fn select_path(&mut self, path: Option<&Path>) {
+        let index = path
+            .and_then(|path| {
+                self.filtered_projects
+                    .iter()
+                    .position(|project| project.path == path)
+            })
+            .unwrap_or(0);
+        self.select(index);
+    }
+
+    /// Lists the discovered projects according to the sort order, removing duplicates.
+    ///
+    /// Nested projects are only listed in nested mode, unless they are workspace members.
+    fn sort_projects(&mut self) {
+        self.discovered.sort();
+        dedup(&mut self.discovered);
+        self.parents = parents(&self.discovered);
+
+        self.projects = self
+            .discovered
+            .iter()
+            .filter(|project| self.is_listed(project))
+            .cloned()
+            .collect();
+        self.sort_order.sort(&mut self.projects);
+    }
+
+    /// Lists or hides the projects nested in another project, keeping the highlighted project.
+    pub fn set_nested(&mut self, nested: bool) {
+        let selected = self.selected().map(|project| project.path.clone());
+
+        self.nested = nested;
+        self.sort_projects();
+        self.refilter();
+        self.select_path(selected.as_deref());
+    }
+
+    /// Lists or hides the nested projects, displaying the mode in the search bar.
+    pub fn toggle_nested(&mut self) {
+        self.set_nested(!self.nested);
+        let shown = if self.nested { "shown" } else { "hidden" };
+        self.notice = Some(format!("nested projects {shown}"));
+    }
+
+    /// Returns the closest project containing the given one, if it is nested.
+    pub fn parent(&self, project: &Project) -> Option<&Path> {
+        self.parents.get(&project.path).map(PathBuf::as_path)
+    }
+
+    /// Changes the order of the project list, keeping the highlighted project.
+    pub fn set_sort_order(&mut self, order: SortOrder) {
+        let selected = self.selected().map(|project| project.path.clone());
+
+        self.sort_order = order;
+        self.sort_projects();
+        self.refilter();
+        self.select_path(selected.as_deref());
+    }
+
+    /// Switches to the next sort order, displaying it in the search bar.
+    pub fn cycle_sort(&mut self) {
+        self.set_sort_order(self.sort_order.next());
+        self.notice = Some(format!("sort: {}", self.sort_order));
+    }
+
     /// Handles the tick event of the terminal.
     pub fn tick(&self) {}
 
-    /// Closes the application
-    pub fn quit(&mut self) {
-        self.state = AppState::Stopped;
+    /// Closes the application without selecting a project.
+    pub fn cancel(&mut self) {
+        self.state = AppState::Cancelled;
     }
 
-    /// Go up one in the list.
+    /// Closes the application, selecting the marked projects, or the highlighted one if none is
+    /// marked.
     ///
-    /// If at the top, go back to bottom.
-    pub fn up(&mut self) {
-        let mut new = self.list_state.selected().unwrap_or(0) + 1;
+    /// Does nothing if there is no project to select.
+    pub fn accept(&mut self) {
+        let marked = self.marked();
 
-        if new >= self.filtered_projects.len() {
-            new = 0;
+        if !marked.is_empty() {
+            self.state = AppState::Selected(marked);
+        } else if let Some(selected) = self.selected() {
+            self.state = AppState::Selected(vec![selected.clone()]);
         }
+    }
 
-        self.list_state.select(Some(new));
-        self.preview.select(self.selected().cloned());
+    /// Marks or unmarks the highlighted project, then goes to the next one.
+    pub fn toggle_mark(&mut self) {
+        if let Some(path) = self.selected().map(|project| project.path.clone()) {
+            if !self.marked.remove(&path) {
+                self.marked.insert(path);
+            }
+            self.move_by(1);
+        }
     }
 
-    /// Go down one in the list.
+    /// Pins or unpins the highlighted project, which stays highlighted as it moves in the list.
+    pub fn toggle_pin(&mut self) {
+        if let Some(path) = self.selected().map(|project| project.path.clone()) {
+            self.pins.toggle(&path);
+            self.refilter();
+            self.select_path(Some(&path));
+        }
+    }
+
+    pub fn is_pinned(&self, project: &Project) -> bool {
+        self.pins.contains(&project.path)
+    }
+
+    /// Unmarks the highlighted project, then goes to the previous one.
+    pub fn unmark(&mut self) {
+        if let Some(path) = self.selected().map(|project| project.path.clone()) {
+            self.marked.remove(&path);
+            self.move_by(-1);
+        }
+    }
+
+    pub fn is_marked(&self, project: &Project) -> bool {
+        self.marked.contains(&project.path)
+    }
+
+    /// Returns the marked projects, in the order of the unfiltered list.
+    pub fn marked(&self) -> Vec<Project> {
+        self.projects
+            .iter()
+            .filter(|project| self.is_marked(project))
+            .cloned()
+            .collect()
+    }
+
+    /// Closes the application, running the given action on the highlighted project.
+    ///
+    /// Does nothing if there is no project to run the action on.
+    pub fn run_action(&mut self, action: Action) {
+        if let Some(selected) = self.selected() {
+            self.state = AppState::Run(action, selected.clone());
+        }
+    }
+
+    /// Opens the action menu with the actions available for the highlighted project.
+    pub fn open_action_menu(&mut self) {
+        if let Some(selected) = self.selected() {
+            let actions = self
+                .actions
+                .iter()
+                .filter(|action| action.is_available(selected))
+                .map(|action| Action {
+                    key: self
+                        .keymap
+                        .keys_for(&Command::Action(action.name.clone()))
+                        .first()
+                        .copied(),
+                    ..action.clone()
+                })
+                .collect();
+            self.action_menu.open(actions);
+        }
+    }
+
+    pub fn close_action_menu(&mut self) {
+        self.action_menu.close();
+    }
+
+    pub fn is_action_menu_open(&self) -> bool {
+        self.action_menu.is_visible()
+    }
+
+    /// Opens the help, listing the key bindings.
+    pub fn open_help(&mut self) {
+        self.help.open(&self.keymap, &self.actions, self.vim);
+    }
+
+    pub fn close_help(&mut self) {
+        self.help.close();
+    }
+
+    pub fn is_help_open(&self) -> bool {
+        self.help.is_visible()
+    }
+
+    /// Scrolls the help by the given number of lines, negative values scroll up.
+    pub fn scroll_help(&mut self, lines: isize) {
+        self.help.scroll_by(lines);
+    }
+
+    /// Go up one in the action menu.
+    pub fn action_menu_up(&mut self) {
+        self.action_menu.up();
+    }
+
+    /// Go down one in the action menu.
+    pub fn action_menu_down(&mut self) {
+        self.action_menu.down();
+    }
+
+    /// Applies an edit to the action menu search.
+    pub fn edit_action_search(&mut self, edit: impl FnOnce(&mut Searchbar)) {
+        self.action_menu.edit_search(edit, &mut self.sorter);
+    }
+
+    /// Closes the application, running the action of the given name on the highlighted project.
+    pub fn run_action_named(&mut self, name: &str) {
+        if let Some(action) = self.actions.iter().find(|action| action.name == name) {
+            self.run_action(action.clone());
+        }
+    }
+
+    /// Runs the action highlighted in the action menu.
+    pub fn run_highlighted_action(&mut self) {
+        if let Some(action) = self.action_menu.selected().cloned() {
+            self.run_action(action);
+        }
+    }
+
+    /// Go up one in the list, as displayed.
+    ///
+    /// If at the top, go back to bottom.
+    pub fn up(&mut self) {
+        self.step(self.layout.order.up());
+    }
+
+    /// Go down one in the list, as displayed.
     ///
     /// If at the bottom, go back to the top.
     pub fn down(&mut self) {
-        let new = self
-            .list_state
-            .selected()
-            .unwrap_or(0)
-            .checked_sub(1)
-            .unwrap_or(self.filtered_projects.len() - 1);
-
-        self.list_state.select(Some(new));
+        self.step(-self.layout.order.up());
+    }
+
+    /// Moves the selection by the given number of items, wrapping around.
+    fn step(&mut self, items: isize) {
+        let len = self.filtered_projects.len() as isize;
+        if len == 0 {
+            return;
+        }
+
+        let selected = self.list_state.selected().unwrap_or(0) as isize;
+        self.select((selected + items).rem_euclid(len) as usize);
+    }
+
+    /// Go up by the number of visible projects, without wrapping around.
+    pub fn page_up(&mut self) {
+        self.scroll_list(self.list_height() as isize);
+    }
+
+    /// Go down by the number of visible projects, without wrapping around.
+    pub fn page_down(&mut self) {
+        self.scroll_list(-(self.list_height() as isize));
+    }
+
+    /// Selects the first project, which is the best match.
+    pub fn first(&mut self) {
+        self.select(0);
+    }
+
+    /// Selects the last project.
+    pub fn last(&mut self) {
+        self.select(usize::MAX);
+    }
+
+    /// Returns the number of projects visible in the list, as of the last render.
+    fn list_height(&self) -> usize {
+        self.list_area.inner(Margin::new(1, 1)).height.max(1) as usize
+    }
+
+    /// Selects the project at the given index in the filtered list, bounded to the list length.
+    pub fn select(&mut self, index: usize) {
+        let index = index.min(self.filtered_projects.len().saturating_sub(1));
+
+        self.list_state.select(Some(index));
         self.preview.select(self.selected().cloned());
     }
 
+    /// Moves the selection by the given number of lines, without wrapping around.
+    ///
+    /// Positive values move up on screen, like [`App::up`].
+    pub fn scroll_list(&mut self, lines: isize) {
+        self.move_by(lines * self.layout.order.up());
+    }
+
+    /// Moves the selection by the given number of items, without wrapping around.
+    fn move_by(&mut self, items: isize) {
+        let selected = self.list_state.selected().unwrap_or(0);
+        self.select(selected.saturating_add_signed(items));
+    }
+
+    /// Returns the index of the project displayed at the given terminal position, if any.
+    pub fn project_at(&self, x: u16, y: u16) -> Option<usize> {
+        let inner = self.list_area.inner(Margin::new(1, 1));
+
+        if !inner.contains(Position::new(x, y)) {
+            return None;
+        }
+
+        let line = match self.layout.order {
+            ListOrder::BottomToTop => inner.bottom() - 1 - y,
+            ListOrder::TopToBottom => y - inner.y,
+        };
+        let index = self.list_state.offset() + line as usize;
+        (index < self.filtered_projects.len()).then_some(index)
+    }
+
+    /// Returns whether the given terminal position is over the project list.
+    pub fn is_over_list(&self, x: u16, y: u16) -> bool {
+        self.list_area.contains(Position::new(x, y))
+    }
+
+    /// Returns whether the given terminal position is over the preview.
+    pub fn is_over_preview(&self, x: u16, y: u16) -> bool {
+        self.preview_area.contains(Position::new(x, y))
+    }
+
+    /// Selects the project clicked at the given terminal position.
+    ///
+    /// Clicking twice on the same project accepts it.
+    pub fn click(&mut self, x: u16, y: u16) {
+        let Some(index) = self.project_at(x, y) else {
+            return;
+        };
+
+        let now = Instant::now();
+        let double_click = self.last_click.is_some_and(|(time, last)| {
+            last == index && now.duration_since(time) <= DOUBLE_CLICK_DELAY
+        });
+
+        self.select(index);
+
+        if double_click {
+            self.last_click = None;
+            self.accept();
+        } else {
+            self.last_click = Some((now, index));
+        }
+    }
+
+    /// Scrolls the preview by the given number of lines, negative values scroll up.
+    pub fn scroll_preview(&mut self, lines: isize) {
+        self.preview.scroll_by(lines);
+    }
+
     /// Filters the projects according to the search prompt.
     ///
     /// Should likely be called on every keystroke.
     pub fn filter_projects(&mut self) {
-        self.filtered_projects = self.sorter.sort(&self.projects, self.searchbar.content());
+        self.refilter();
         self.preview.select(self.selected().cloned())
     }
 
+    /// Filters the projects matching the search, pinned projects first among equally good matches.
+    ///
+    /// Every project is listed when the search is empty, by decreasing frecency if the history is
+    /// enabled. Otherwise the frecency boosts the score of the matches.
+    fn refilter(&mut self) {
+        let pins = &self.pins;
+        let history = self.history.as_ref();
+        let unpinned = |project: &Project| !pins.contains(&project.path);
+
+        self.filtered_projects = if self.searchbar.content().is_empty() {
+            let mut projects = self.projects.clone();
+            let frecency =
+                |project: &Project| history.map_or(0., |history| history.frecency(&project.path));
+            projects.sort_by(|a, b| {
+                unpinned(a)
+                    .cmp(&unpinned(b))
+                    .then_with(|| frecency(b).total_cmp(&frecency(a)))
+            });
+            projects
+        } else {
+            let boost =
+                |project: &Project| history.map_or(1., |history| history.boost(&project.path));
+            self.sorter.sort_with(
+                &self.projects,
+                self.searchbar.content(),
+                |project, score| ((score as f64 / boost(project)) as i16, unpinned(project)),
+            )
+        };
+    }
+
     /// Returns the currently selected project
     pub fn selected(&self) -> Option<&Project> {
         let selected = self.list_state.selected().unwrap_or(0);
         self.filtered_projects.get(selected)
     }
 
+    /// Returns the current search.
+    pub fn search(&self) -> &str {
+        self.searchbar.content()
+    }
+
     /// Add one character to the current search.
-    ///
-    /// Also reset the currently selected item and triggers the list filtering.
     pub fn push_search(&mut self, c: char) {
-        self.searchbar.push(c);
-        self.list_state.select(Some(0));
-        self.filter_projects();
+        self.edit_search(|searchbar| searchbar.push(c));
     }
 
-    /// Remove one character from the current search.
+    /// Inserts pasted text in the search of the focused input, without newlines.
     ///
-    /// Triggers a new list filtering.
-    pub fn pop_search(&mut self) {
-        self.searchbar.pop();
-        if self.searchbar.content().is_empty() {
-            self.filtered_projects.clone_from(&self.projects);
+    /// The list is filtered once for the whole text.
+    pub fn paste(&mut self, text: &str) {
+        let text: String = text.chars().filter(|c| !matches!(c, '\n' | '\r')).collect();
+
+        if self.is_action_menu_open() {
+            self.edit_action_search(|searchbar| searchbar.push_str(&text));
         } else {
-            self.filter_projects();
+            self.edit_search(|searchbar| searchbar.push_str(&text));
         }
     }
 
+    /// Remove one character from the current search.
+    pub fn pop_search(&mut self) {
+        self.edit_search(Searchbar::pop);
+    }
+
     /// Reset the current search.
     pub fn clear_search(&mut self) {
-        self.searchbar.clear();
-        self.filtered_projects.clone_from(&self.projects);
+        self.edit_search(Searchbar::clear);
+    }
+
+    /// Applies an edit to the current search, such as moving the cursor or deleting a word.
+    ///
+    /// If the search changed, reset the currently selected item and triggers the list filtering.
+    pub fn edit_search(&mut self, edit: impl FnOnce(&mut Searchbar)) {
+        let previous = self.searchbar.content().to_string();
+        edit(&mut self.searchbar);
+
+        if self.searchbar.content() == previous {
+            return;
+        }
+
+        self.list_state.select(Some(0));
+        self.filter_projects();
     }
 
     pub fn toggle_preview(&mut self) {
         self.preview.toggle();
     }
+
+    /// Switches to the next built-in theme, displaying its name in the search bar.
+    pub fn cycle_theme(&mut self) {
+        let names: Vec<_> = Theme::builtin_names().collect();
+        let next = self.previewed_theme.map_or(0, |current| {
+            let index = names.iter().position(|name| *name == current);
+            index.map_or(0, |index| (index + 1) % names.len())
+        });
+
+        self.previewed_theme = Some(names[next]);
+        self.notice = Some(format!("theme: {}", names[next]));
+        let next_theme = Theme::builtin(names[next]).expect("built-in theme should exist");
+        set_theme(next_theme.adapt(theme().colors));
+    }
+
+    /// Returns the name of the theme previewed with [`App::cycle_theme`], if any.
+    pub fn previewed_theme(&self) -> Option<&'static str> {
+        self.previewed_theme
+    }
+
+    /// Enables or disables vim-style modal editing, starting in insert mode.
+    pub fn set_vim(&mut self, vim: bool) {
+        self.vim = vim;
+        self.set_mode(Mode::Insert);
+    }
+
+    pub fn is_vim(&self) -> bool {
+        self.vim
+    }
+
+    pub fn mode(&self) -> Mode {
+        self.mode
+    }
+
+    /// Returns the key waiting for the next one in normal mode, forgetting it.
+    pub fn take_pending_key(&mut self) -> Option<KeyChord> {
+        self.pending_key.take()
+    }
+
+    /// Waits for the next key to form a normal mode command with this one.
+    pub fn set_pending_key(&mut self, key: KeyChord) {
+        self.pending_key = Some(key);
+    }
+
+    /// Switches the editing mode, displaying it in the search bar if modal editing is enabled.
+    pub fn set_mode(&mut self, mode: Mode) {
+        self.mode = mode;
+        self.pending_key = None;
+        self.searchbar
+            .set_indicator(self.vim.then(|| mode.to_string()));
+    }
+}
+
+/// Removes the projects listed twice from a list sorted by path, keeping their workspace.
+fn dedup(projects: &mut Vec<Project>) {
+    projects.dedup_by(|duplicate, kept| {
+        if duplicate.path != kept.path {
+            return false;
+        }
+        if kept.workspace.is_none() {
+            kept.workspace = duplicate.workspace.take();
+        }
+        true
+    });
+}
+
+/// Merges two sorted lists, the items of the first one coming first on ties.
+fn merge<T>(a: Vec<T>, b: Vec<T>, mut compare: impl FnMut(&T, &T) -> Ordering) -> Vec<T> {
+    let mut merged = Vec::with_capacity(a.len() + b.len());
+    let (mut a, mut b) = (a.into_iter().peekable(), b.into_iter().peekable());
+
+    while let (Some(x), Some(y)) = (a.peek(), b.peek()) {
+        let next = if compare(y, x).is_lt() {
+            &mut b
+        } else {
+            &mut a
+        };
+        merged.extend(next.next());
+    }
+
+    merged.extend(a);
+    merged.extend(b);
+    merged
+}
+
+/// Returns the closest parent project of each nested project.
+///
+/// The projects must be sorted by path, so that nested projects directly follow their parents.
+fn parents(projects: &[Project]) -> HashMap<PathBuf, PathBuf> {
+    let mut ancestors: Vec<&Path> = Vec::new();
+    let mut parents = HashMap::new();
+
+    for project in projects {
+        while ancestors
+            .last()
+            .is_some_and(|ancestor| !project.path.starts_with(ancestor))
+        {
+            ancestors.pop();
+        }
+
+        if let Some(parent) = ancestors.last() {
+            parents.insert(project.path.clone(), parent.to_path_buf());
+        }
+        ancestors.push(&project.path);
+    }
+
+    parents
 }
 
 impl Widget for &mut App {
@@ -171,29 +822,180 @@ impl Widget for &mut App {
     where
         Self: Sized,
     {
-        let show_preview = self.preview.is_visible() && area.width > 100;
-        let projects_pane_width = if show_preview { 70 } else { 100 };
-
-        let [projects_pane, preview_pane] = Layout::new(
-            Direction::Horizontal,
-            [
-                Constraint::Percentage(projects_pane_width),
-                Constraint::Min(0),
-            ],
-        )
-        .areas(area);
-
-        let [project_chunk, searchbar_chunk] = Layout::new(
-            Direction::Vertical,
-            [Constraint::Min(3), Constraint::Length(3)],
-        )
-        .areas(projects_pane);
+        let (projects_pane, preview_pane) = self.layout.split(area, self.preview.is_visible());
+        let (project_chunk, searchbar_chunk) = self.layout.split_projects(projects_pane);
+
+        let help_key = self.keymap.keys_for(&Command::Help).first().copied();
+        let hint = match &self.notice {
+            Some(notice) => Some(notice.clone()),
+            None => help_key.map(|key| format!("{key} for help")),
+        };
+        self.searchbar.set_hint(hint);
+
+        self.list_area = project_chunk;
+        self.preview_area = preview_pane.unwrap_or_default();
 
         projects_list::render(project_chunk, buf, self);
         self.searchbar.render(searchbar_chunk, buf);
 
-        if show_preview {
+        if let Some(preview_pane) = preview_pane {
             self.preview.render(preview_pane, buf)
         }
+
+        if self.action_menu.is_visible() {
+            let width = (projects_pane.width / 2).max(40).min(projects_pane.width);
+            let height = self.action_menu.height().min(projects_pane.height);
+            let popup = Rect {
+                x: projects_pane.x + (projects_pane.width - width) / 2,
+                y: projects_pane.y + (projects_pane.height - height) / 2,
+                width,
+                height,
+            };
+
+            self.action_menu.render(popup, buf);
+        }
+
+        if self.help.is_visible() {
+            let width = area.width.min(100);
+            let height = self.help.height().min(area.height);
+            let popup = Rect {
+                x: area.x + (area.width - width) / 2,
+                y: area.y + (area.height - height) / 2,
+                width,
+                height,
+            };
+
+            self.help.render(popup, buf);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Returns an app listing 30 projects, 10 of them being visible at once.
+    fn app() -> App {
+        let mut app = App::new((0..30).map(|i| Project::test(format!("/home/project{i:02}"))));
+        app.list_area = Rect::new(0, 0, 40, 12);
+        app
+    }
+
+    fn selected(app: &App) -> Option<usize> {
+        app.list_state.selected()
+    }
+
+    fn paths(projects: &[Project]) -> Vec<&Path> {
+        projects
+            .iter()
+            .map(|project| project.path.as_path())
+            .collect()
+    }
+
+    #[test]
+    fn add_projects() {
+        let mut app = App::new([Project::test("/home/b/c"), Project::test("/home/d")]);
+        app.add_projects(vec![Project::test("/home/b/c/e"), Project::test("/home/a")]);
+        app.add_projects(vec![Project::test("/home/b"), Project::test("/home/b/c")]);
+        app.add_projects(vec![Project {
+            workspace: Some(PathBuf::from("/home/b")),
+            ..Project::test("/home/b/c/e")
+        }]);
+
+        assert_eq!(
+            paths(&app.projects),
+            ["/home/a", "/home/b", "/home/b/c/e", "/home/d"].map(Path::new)
+        );
+        assert_eq!(
+            app.parent(&Project::test("/home/b/c/e")),
+            Some(Path::new("/home/b/c"))
+        );
+        assert_eq!(
+            app.parent(&Project::test("/home/b/c")),
+            Some(Path::new("/home/b"))
+        );
+
+        app.set_sort_order(SortOrder::Depth);
+        app.add_projects(vec![Project::test("/home/f/g"), Project::test("/home/f")]);
+        assert_eq!(
+            paths(&app.projects),
+            ["/home/a", "/home/b", "/home/d", "/home/f", "/home/b/c/e"].map(Path::new)
+        );
+    }
+
+    #[test]
+    fn sort_order() {
+        let mut app = App::new([("/home/a", 1), ("/home/b", 3), ("/home/c", 2)].map(
+            |(path, secs)| Project {
+                modified: Some(std::time::UNIX_EPOCH + Duration::from_secs(secs)),
+                ..Project::test(path)
+            },
+        ));
+        app.select(1);
+
+        app.set_sort_order(SortOrder::Modified);
+        assert_eq!(
+            paths(&app.filtered_projects),
+            ["/home/b", "/home/c", "/home/a"].map(Path::new)
+        );
+        assert_eq!(app.selected().unwrap().path, Path::new("/home/b"));
+
+        app.add_projects(vec![Project::test("/home/d")]);
+        assert_eq!(
+            paths(&app.projects),
+            ["/home/b", "/home/c", "/home/a", "/home/d"].map(Path::new)
+        );
+
+        app.cycle_sort();
+        assert_eq!(app.sort_order, SortOrder::Depth);
+        assert_eq!(app.notice.as_deref(), Some("sort: depth"));
+        assert_eq!(
+            paths(&app.filtered_projects),
+            ["/home/a", "/home/b", "/home/c", "/home/d"].map(Path::new)
+        );
+        assert_eq!(app.selected().unwrap().path, Path::new("/home/b"));
+    }
+
+    #[test]
+    fn paging() {
+        let mut app = app();
+
+        app.page_up();
+        assert_eq!(selected(&app), Some(10));
+        app.page_up();
+        app.page_up();
+        app.page_up();
+        assert_eq!(selected(&app), Some(29));
+
+        app.page_down();
+        assert_eq!(selected(&app), Some(19));
+        app.page_down();
+        app.page_down();
+        assert_eq!(selected(&app), Some(0));
+    }
+
+    #[test]
+    fn paging_top_to_bottom() {
+        let mut app = app();
+        app.layout.order = ListOrder::TopToBottom;
+
+        app.page_down();
+        assert_eq!(selected(&app), Some(10));
+        app.page_up();
+        assert_eq!(selected(&app), Some(0));
+    }
+
+    #[test]
+    fn first_and_last() {
+        let mut app = app();
+
+        app.last();
+        assert_eq!(selected(&app), Some(29));
+        app.first();
+        assert_eq!(selected(&app), Some(0));
+
+        app.paste("project1");
+        app.last();
+        assert_eq!(selected(&app), Some(app.filtered_projects.len() - 1));
     }
 }
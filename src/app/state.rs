@@ -1,12 +1,53 @@
+use std::{fmt::Display, process::ExitCode};
+
+use crate::{action::Action, project::Project};
+
+/// Exit code used when the user cancels the selection.
+///
+/// Same as a process interrupted by `SIGINT`, so shells treat it the same as `Ctrl-C`.
+pub const EXIT_CANCELLED: u8 = 130;
+
 #[derive(Debug, Default)]
 pub enum AppState {
     #[default]
     Running,
-    Stopped,
+    /// Projects have been selected, either the marked ones or the highlighted one.
+    Selected(Vec<Project>),
+    /// An action should be run on a project once the terminal is restored.
+    Run(Action, Project),
+    /// The user exited without selecting anything.
+    Cancelled,
 }
 
 impl AppState {
     pub fn is_running(&self) -> bool {
         matches!(self, Self::Running)
     }
+
+    /// Returns the exit code the process should exit with in this state.
+    pub fn exit_code(&self) -> ExitCode {
+        match self {
+            Self::Running | Self::Selected(_) | Self::Run(..) => ExitCode::SUCCESS,
+            Self::Cancelled => ExitCode::from(EXIT_CANCELLED),
+        }
+    }
+}
+
+/// Editing mode of the search, when vim-style modal editing is enabled.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum Mode {
+    /// Keys are typed in the search.
+    #[default]
+    Insert,
+    /// Keys navigate the list, like in vim's normal mode.
+    Normal,
+}
+
+impl Display for Mode {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Insert => f.write_str("INSERT"),
+            Self::Normal => f.write_str("NORMAL"),
+        }
+    }
 }
@@ -1,13 +1,179 @@
+use std::{
+    fs::File,
+    io::{self, Write},
+    path::PathBuf,
+};
+
 use argh::FromArgs;
 
-use crate::theme::ThemeVariant;
+use crate::{app::AppResult, project::SortOrder, shell::Shell, theme::ColorMode, tui::Height};
 
 /// Find Project.
 ///
 /// A TUI to find projects and navigate to them.
 #[derive(FromArgs)]
 pub struct Cli {
-    /// theme to use, can be "light" or "dark" (default)
-    #[argh(option, default = "ThemeVariant::default()")]
-    pub theme: ThemeVariant,
+    /// theme to use: "dark" (default), "light", "auto", the name of a theme in
+    /// $XDG_CONFIG_HOME/fp/themes or the path to a theme file
+    #[argh(option)]
+    pub theme: Option<String>,
+
+    /// when to use colors: "auto" (default) detects what the terminal supports, "always" or
+    /// "never"
+    #[argh(option, default = "ColorMode::Auto")]
+    pub color: ColorMode,
+
+    /// order of the project list when nothing is searched: "path" (default), "name", "modified"
+    /// or "depth"
+    #[argh(option)]
+    pub sort: Option<SortOrder>,
+
+    /// configuration file to use instead of $XDG_CONFIG_HOME/fp/config.toml
+    #[argh(option)]
+    pub config: Option<PathBuf>,
+
+    /// write the selected path to this file instead of stdout
+    #[argh(option)]
+    pub output_file: Option<PathBuf>,
+
+    /// write the selected path to this file descriptor instead of stdout
+    #[argh(option)]
+    pub output_fd: Option<i32>,
+
+    /// do not capture the mouse, keeping the terminal text selection
+    #[argh(switch)]
+    pub no_mouse: bool,
+
+    /// render below the prompt instead of fullscreen, using 40% of the terminal height
+    #[argh(switch)]
+    pub inline: bool,
+
+    /// render below the prompt with this height, in lines or percent like "40%"
+    #[argh(option)]
+    pub height: Option<Height>,
+
+    #[argh(subcommand)]
+    pub command: Option<Command>,
+}
+
+impl Cli {
+    /// Opens the output the selected path should be written to.
+    ///
+    /// This is stdout unless `--output-file` or `--output-fd` is given.
+    pub fn output(&self) -> AppResult<Box<dyn Write>> {
+        match (&self.output_file, self.output_fd) {
+            (Some(_), Some(_)) => {
+                Err("--output-file and --output-fd cannot be used together".into())
+            }
+            (Some(path), None) => Ok(Box::new(File::create(path)?)),
+            (None, Some(fd)) => open_fd(fd),
+            (None, None) => Ok(Box::new(io::stdout())),
+        }
+    }
+
+    /// Returns the height of the interface when rendered inline, or `None` for fullscreen.
+    pub fn inline_height(&self) -> Option<Height> {
+        self.height.or(self.inline.then(Height::default))
+    }
+}
+
+#[cfg(unix)]
+fn open_fd(fd: i32) -> AppResult<Box<dyn Write>> {
+    use std::{mem, os::fd::FromRawFd};
+
+    // SAFETY: `fcntl` with `F_GETFD` only reads the descriptor flags and fails on a closed or
+    // invalid descriptor.
+    if fd < 0 || unsafe { libc::fcntl(fd, libc::F_GETFD) } == -1 {
+        return Err(format!("invalid file descriptor {fd}").into());
+    }
+
+    // SAFETY: the descriptor was checked to be open above, before fp opened any file of its own,
+    // and it is never closed since the caller keeps owning it.
+    let file = unsafe { File::from_raw_fd(fd) };
+    Ok(Box::new(FdWriter(mem::ManuallyDrop::new(file))))
+}
+
+/// Writes to a file descriptor owned by the caller, without closing it when dropped.
+#[cfg(unix)]
+struct FdWriter(std::mem::ManuallyDrop<File>);
+
+#[cfg(unix)]
+impl Write for FdWriter {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        self.0.write(buf)
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        self.0.flush()
+    }
+}
+
+#[cfg(not(unix))]
+fn open_fd(_fd: i32) -> AppResult<Box<dyn Write>> {
+    Err("--output-fd is not supported on this platform".into())
 }
+
+#[derive(FromArgs)]
+#[argh(subcommand)]
+pub enum Command {
+    Init(Init),
+    Keys(Keys),
+    Themes(Themes),
+    Pin(Pin),
+    History(History),
+}
+
+/// Print the shell integration script.
+#[derive(FromArgs)]
+#[argh(subcommand, name = "init")]
+pub struct Init {
+    /// shell to generate the script for: bash, zsh, fish, nushell or powershell
+    #[argh(positional)]
+    pub shell: Shell,
+
+    /// do not bind Ctrl-G to fp
+    #[argh(switch)]
+    pub no_key_binding: bool,
+}
+
+/// Print the effective key bindings.
+#[derive(FromArgs)]
+#[argh(subcommand, name = "keys")]
+pub struct Keys {}
+
+/// List the available themes, press Alt-T in fp to preview them.
+#[derive(FromArgs)]
+#[argh(subcommand, name = "themes")]
+pub struct Themes {}
+
+/// Pin a project so that it is always listed first, or list the pinned projects.
+#[derive(FromArgs)]
+#[argh(subcommand, name = "pin")]
+pub struct Pin {
+    /// directory of the project to pin
+    #[argh(positional)]
+    pub path: Option<PathBuf>,
+
+    /// unpin the project instead
+    #[argh(switch)]
+    pub remove: bool,
+}
+
+/// Manage the history of the selected projects, enabled by `history = true`.
+#[derive(FromArgs)]
+#[argh(subcommand, name = "history")]
+pub struct History {
+    #[argh(subcommand)]
+    pub command: HistoryCommand,
+}
+
+#[derive(FromArgs)]
+#[argh(subcommand)]
+pub enum HistoryCommand {
+    Clear(HistoryClear),
+}
+
+/// Forget every selected project.
+#[derive(FromArgs)]
+#[argh(subcommand, name = "clear")]
+pub struct HistoryClear {}
@@ -0,0 +1,228 @@
+use std::{
+    collections::BTreeMap,
+    env, fs, io,
+    path::{Path, PathBuf},
+};
+
+use serde::Deserialize;
+
+use crate::{
+    action::Action,
+    app::AppResult,
+    keymap::{Keymap, Keys},
+    layout::Layout,
+    project::SortOrder,
+};
+
+/// User configuration, read from `$XDG_CONFIG_HOME/fp/config.toml`.
+#[derive(Debug, Default, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct Config {
+    /// Actions that can be run on the selected project, by name.
+    actions: BTreeMap<String, Action>,
+    /// Keys bound to each command, replacing the default ones.
+    keys: BTreeMap<String, Keys>,
+    /// Enables vim-style modal editing, `Esc` switching to normal mode instead of exiting.
+    pub vim: bool,
+    /// Position and size of the parts of the interface.
+    pub layout: Layout,
+    /// Theme to use, by name or path, overridden by `--theme`.
+    pub theme: Option<String>,
+    /// Records the selected projects to rank them by frecency.
+    pub history: bool,
+    /// Order of the project list when nothing is searched, overridden by `--sort`.
+    pub sort: Option<SortOrder>,
+    /// Lists the projects nested in another project, like the packages of a monorepo.
+    pub nested: bool,
+}
+
+impl Config {
+    /// Returns the directory holding the configuration of `fp`.
+    pub fn dir() -> Option<PathBuf> {
+        env::var_os("XDG_CONFIG_HOME")
+            .filter(|dir| !dir.is_empty())
+            .map(PathBuf::from)
+            .or_else(|| env::var_os("HOME").map(|home| PathBuf::from(home).join(".config")))
+            .map(|dir| dir.join("fp"))
+    }
+
+    /// Returns the directory holding the state of `fp`, like pinned projects.
+    pub fn state_dir() -> Option<PathBuf> {
+        env::var_os("XDG_STATE_HOME")
+            .filter(|dir| !dir.is_empty())
+            .map(PathBuf::from)
+            .or_else(|| env::var_os("HOME").map(|home| PathBuf::from(home).join(".local/state")))
+            .map(|dir| dir.join("fp"))
+    }
+
+    /// Loads the configuration from the given file, or from the default location.
+    ///
+    /// A missing file at the default location is not an error, the default configuration is used
+    /// instead.
+    pub fn load(path: Option<&Path>) -> AppResult<Self> {
+        let (path, required) = match path {
+            Some(path) => (path.to_path_buf(), true),
+            None => match Self::dir() {
+                Some(dir) => (dir.join("config.toml"), false),
+                None => return Ok(Self::default()),
+            },
+        };
+
+        match fs::read_to_string(&path) {
+            Ok(content) => Self::parse(&content)
+                .map_err(|err| format!("invalid configuration '{}': {err}", path.display()).into()),
+            Err(err) if err.kind() == io::ErrorKind::NotFound && !required => Ok(Self::default()),
+            Err(err) => Err(format!("cannot read '{}': {err}", path.display()).into()),
+        }
+    }
+
+    /// Parses a configuration from its TOML representation.
+    pub fn parse(content: &str) -> Result<Self, toml::de::Error> {
+        let mut config: Self = toml::from_str(content)?;
+
+        for (name, action) in &mut config.actions {
+            action.name.clone_from(name);
+        }
+
+        Ok(config)
+    }
+
+    /// Returns the built-in actions followed by the configured ones, sorted by name.
+    ///
+    /// A configured action replaces the built-in action of the same name.
+    pub fn actions(&self) -> Vec<Action> {
+        let mut actions: Vec<_> = Action::builtins()
+            .into_iter()
+            .filter(|action| !self.actions.contains_key(&action.name))
+            .collect();
+
+        actions.extend(self.actions.values().cloned());
+        actions
+    }
+
+    /// Returns the keymap, made of the default keys, the configured keys and the action keys.
+    pub fn keymap(&self) -> AppResult<Keymap> {
+        Keymap::new(&self.keys, &self.actions())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crossterm::event::{KeyCode, KeyModifiers};
+
+    use super::*;
+    use crate::{
+        action::Operation,
+        key::KeyChord,
+        layout::{ListOrder, PreviewPosition, SearchbarPosition},
+    };
+
+    #[test]
+    fn empty() {
+        let config = Config::parse("").unwrap();
+
+        assert_eq!(config.actions(), Action::builtins());
+    }
+
+    #[test]
+    fn actions() {
+        let config = Config::parse(
+            r#"
+            [actions.edit]
+            command = "nvim {path}"
+            key = "ctrl-e"
+
+            [actions.code]
+            command = "code {path}"
+            description = "Open in VS Code"
+            "#,
+        )
+        .unwrap();
+        let actions = config.actions();
+        let (builtins, configured) = actions.split_at(actions.len() - 2);
+
+        assert!(builtins.iter().all(|action| action.name != "edit"));
+        assert_eq!(
+            configured,
+            [
+                Action {
+                    name: "code".to_string(),
+                    description: Some("Open in VS Code".to_string()),
+                    operation: Operation::Command("code {path}".to_string()),
+                    key: None,
+                },
+                Action {
+                    name: "edit".to_string(),
+                    description: None,
+                    operation: Operation::Command("nvim {path}".to_string()),
+                    key: Some(KeyChord::new(KeyModifiers::CONTROL, KeyCode::Char('e'))),
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn keys() {
+        let config = Config::parse(
+            r#"
+            [keys]
+            up = ["up", "ctrl-p"]
+            clear = "ctrl-l"
+            "#,
+        )
+        .unwrap();
+
+        assert_eq!(
+            config.keys["up"],
+            Keys::Many(vec!["up".parse().unwrap(), "ctrl-p".parse().unwrap()])
+        );
+        assert_eq!(config.keys["clear"], Keys::One("ctrl-l".parse().unwrap()));
+        assert!(config.keymap().is_ok());
+    }
+
+    #[test]
+    fn vim() {
+        assert!(!Config::parse("").unwrap().vim);
+        assert!(Config::parse("vim = true").unwrap().vim);
+    }
+
+    #[test]
+    fn layout() {
+        let config = Config::parse(
+            r#"
+            [layout]
+            preview = "bottom"
+            preview-size = 40
+            searchbar = "top"
+            order = "top-to-bottom"
+            "#,
+        )
+        .unwrap();
+
+        assert_eq!(config.layout.preview, PreviewPosition::Bottom);
+        assert_eq!(config.layout.searchbar, SearchbarPosition::Top);
+        assert_eq!(config.layout.order, ListOrder::TopToBottom);
+        assert_eq!(config.layout.preview_min_width, 100);
+
+        assert!(Config::parse("[layout]\npreview-size = 100").is_err());
+    }
+
+    #[test]
+    fn invalid_key() {
+        let err = Config::parse(
+            r#"
+            [actions.edit]
+            command = "nvim {path}"
+            key = "hyper-e"
+            "#,
+        )
+        .unwrap_err();
+
+        assert!(err.to_string().contains("unknown modifier 'hyper'"));
+    }
+
+    #[test]
+    fn unknown_field() {
+        assert!(Config::parse("foo = 1").is_err());
+    }
+}
@@ -6,10 +6,10 @@ use std::{
 
 use crossterm::event::{self, Event as CrosstermEvent, KeyEvent, MouseEvent};
 
-use crate::app::AppResult;
+use crate::{app::AppResult, project::Project};
 
 /// Terminal events.
-#[derive(Clone, Copy, Debug)]
+#[derive(Clone, Debug)]
 pub enum Event {
     /// Terminal tick.
     Tick,
@@ -19,6 +19,12 @@ pub enum Event {
     Mouse(MouseEvent),
     /// Terminal resize.
     Resize(u16, u16),
+    /// Text pasted in the terminal.
+    Paste(String),
+    /// Projects found by the background discovery.
+    Projects(Vec<Project>),
+    /// The background discovery is finished.
+    ScanFinished,
 }
 
 /// Terminal event handler.
@@ -54,7 +60,7 @@ impl EventHandler {
                             CrosstermEvent::Resize(w, h) => sender.send(Event::Resize(w, h)),
                             CrosstermEvent::FocusGained => Ok(()),
                             CrosstermEvent::FocusLost => Ok(()),
-                            CrosstermEvent::Paste(_) => unimplemented!(),
+                            CrosstermEvent::Paste(text) => sender.send(Event::Paste(text)),
                         }
                         .expect("failed to send terminal event")
                     }
@@ -73,6 +79,11 @@ impl EventHandler {
         }
     }
 
+    /// Returns a sender to push events from other threads, like the project discovery.
+    pub fn sender(&self) -> mpsc::Sender<Event> {
+        self.sender.clone()
+    }
+
     /// Receive the next event from the handler thread.
     ///
     /// This function will always block the current thread if
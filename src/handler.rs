@@ -1,37 +1,364 @@
-use std::env::current_dir;
+use crossterm::event::{KeyCode, KeyEvent, KeyModifiers, MouseButton, MouseEvent, MouseEventKind};
 
-use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
-
-use crate::app::{App, AppResult};
+use crate::{
+    app::{App, AppResult, Mode},
+    key::KeyChord,
+    keymap::{normal_command, Command, NormalCommand, NORMAL_MODE},
+    widgets::Searchbar,
+};
 
 /// Handles the key events and updates the state of [`App`].
 pub fn handle_key_events(key_event: KeyEvent, app: &mut App) -> AppResult<()> {
-    match (key_event.modifiers, key_event.code) {
-        // Exit application on `Ctrl-C` or `ESC`
-        (KeyModifiers::CONTROL, KeyCode::Char('c')) | (_, KeyCode::Esc) => {
-            print!("{}", current_dir()?.display());
-            app.quit();
+    if app.is_help_open() {
+        let normal = match normal_command(&[KeyChord::from(key_event)]) {
+            Some(NormalCommand::Run(command)) if app.mode() == Mode::Normal => Some(command),
+            _ => None,
+        };
+        let command = normal.or_else(|| app.keymap.get(&key_event)).cloned();
+        return handle_help_command(command, app);
+    }
+
+    if app.mode() == Mode::Normal && !app.is_action_menu_open() {
+        return handle_normal_mode(key_event, app);
+    }
+
+    // the arrow keys always move in the action menu, whatever the keymap
+    if app.is_action_menu_open() && key_event.modifiers == KeyModifiers::NONE {
+        match key_event.code {
+            KeyCode::Up => {
+                app.action_menu_up();
+                return Ok(());
+            }
+            KeyCode::Down => {
+                app.action_menu_down();
+                return Ok(());
+            }
+            _ => {}
         }
-        // move up
-        (KeyModifiers::CONTROL, KeyCode::Char('k')) => app.up(),
-        // move down
-        (KeyModifiers::CONTROL, KeyCode::Char('j')) => app.down(),
-        // Clear search box
-        (KeyModifiers::CONTROL, KeyCode::Char('u')) => app.clear_search(),
-        // Toggle preview
-        (KeyModifiers::ALT, KeyCode::Char('p')) => app.toggle_preview(),
+    }
+
+    if app.is_vim() && !app.is_action_menu_open() && key_event.code == KeyCode::Esc {
+        app.set_mode(Mode::Normal);
+        return Ok(());
+    }
+
+    let Some(command) = app.keymap.get(&key_event).cloned() else {
         // search input
-        (_, KeyCode::Char(c)) => app.push_search(c),
-        // search input del
-        (_, KeyCode::Backspace) => app.pop_search(),
-        // select project
-        (_, KeyCode::Enter) => {
-            if let Some(selected) = app.selected() {
-                print!("{}", selected.path.display());
-                app.quit()
+        if let KeyCode::Char(c) = key_event.code {
+            if app.is_action_menu_open() {
+                app.edit_action_search(|searchbar| searchbar.push(c));
+            } else {
+                app.push_search(c);
             }
         }
+        return Ok(());
+    };
+
+    if let Some(edit) = search_edit(&command) {
+        if app.is_action_menu_open() {
+            app.edit_action_search(edit);
+        } else {
+            app.edit_search(edit);
+        }
+        return Ok(());
+    }
+
+    if app.is_action_menu_open() {
+        return handle_action_menu_command(command, app);
+    }
+
+    handle_command(command, app);
+    Ok(())
+}
+
+/// Handles the key events in normal mode, where keys navigate the list instead of being typed in
+/// the search.
+///
+/// Keys without a normal mode meaning run their usual command, except for search edits.
+fn handle_normal_mode(key_event: KeyEvent, app: &mut App) -> AppResult<()> {
+    let key = KeyChord::from(key_event);
+    let pending = app.take_pending_key();
+
+    // a pending key not followed by the rest of its sequence is dropped
+    let sequences = [pending.map(|pending| vec![pending, key]), Some(vec![key])];
+    for sequence in sequences.into_iter().flatten() {
+        match normal_command(&sequence) {
+            Some(NormalCommand::Run(command)) => handle_command(command.clone(), app),
+            Some(NormalCommand::Insert) => app.set_mode(Mode::Insert),
+            None => continue,
+        }
+        return Ok(());
+    }
+
+    if NORMAL_MODE
+        .iter()
+        .any(|(keys, _)| keys.len() > 1 && keys[0] == key)
+    {
+        app.set_pending_key(key);
+    } else {
+        handle_bound_command(key_event, app);
+    }
+    Ok(())
+}
+
+/// Runs the command bound to the key in normal mode, ignoring search edits.
+fn handle_bound_command(key_event: KeyEvent, app: &mut App) {
+    match app.keymap.get(&key_event).cloned() {
+        Some(command) if search_edit(&command).is_none() => handle_command(command, app),
+        _ => {}
+    }
+}
+
+/// Runs a command on the project list.
+fn handle_command(command: Command, app: &mut App) {
+    match command {
+        Command::Cancel => app.cancel(),
+        Command::Accept => app.accept(),
+        Command::Up => app.up(),
+        Command::Down => app.down(),
+        Command::PageUp => app.page_up(),
+        Command::PageDown => app.page_down(),
+        Command::First => app.first(),
+        Command::Last => app.last(),
+        Command::TogglePreview => app.toggle_preview(),
+        Command::ToggleMark => app.toggle_mark(),
+        Command::Unmark => app.unmark(),
+        Command::TogglePin => app.toggle_pin(),
+        Command::ActionMenu => app.open_action_menu(),
+        Command::Help => app.open_help(),
+        Command::CycleTheme => app.cycle_theme(),
+        Command::CycleSort => app.cycle_sort(),
+        Command::ToggleNested => app.toggle_nested(),
+        Command::Action(name) => app.run_action_named(&name),
+        _ => {}
+    }
+}
+
+/// Handles the mouse events and updates the state of [`App`].
+pub fn handle_mouse_events(mouse_event: MouseEvent, app: &mut App) -> AppResult<()> {
+    if app.is_action_menu_open() || app.is_help_open() {
+        return Ok(());
+    }
+
+    let (x, y) = (mouse_event.column, mouse_event.row);
+
+    match mouse_event.kind {
+        MouseEventKind::Down(MouseButton::Left) => app.click(x, y),
+        MouseEventKind::ScrollUp if app.is_over_list(x, y) => app.scroll_list(1),
+        MouseEventKind::ScrollDown if app.is_over_list(x, y) => app.scroll_list(-1),
+        MouseEventKind::ScrollUp if app.is_over_preview(x, y) => app.scroll_preview(-3),
+        MouseEventKind::ScrollDown if app.is_over_preview(x, y) => app.scroll_preview(3),
+        _ => {}
+    }
+    Ok(())
+}
+
+/// Handles the commands while the help is open, any other key being ignored.
+fn handle_help_command(command: Option<Command>, app: &mut App) -> AppResult<()> {
+    match command {
+        Some(Command::Cancel | Command::Help) => app.close_help(),
+        Some(Command::Up) => app.scroll_help(-1),
+        Some(Command::Down) => app.scroll_help(1),
+        Some(Command::PageUp) => app.scroll_help(-10),
+        Some(Command::PageDown) => app.scroll_help(10),
+        _ => {}
+    }
+    Ok(())
+}
+
+/// Handles the commands while the action menu is open.
+fn handle_action_menu_command(command: Command, app: &mut App) -> AppResult<()> {
+    match command {
+        Command::Cancel | Command::ActionMenu => app.close_action_menu(),
+        Command::Accept => app.run_highlighted_action(),
+        Command::Up => app.action_menu_up(),
+        Command::Down => app.action_menu_down(),
+        Command::TogglePreview => app.toggle_preview(),
+        Command::Action(name) => app.run_action_named(&name),
         _ => {}
     }
     Ok(())
 }
+
+/// Returns the edit of the search input corresponding to the command, if any.
+fn search_edit(command: &Command) -> Option<fn(&mut Searchbar)> {
+    let edit = match command {
+        Command::Clear => Searchbar::clear,
+        Command::Backspace => Searchbar::pop,
+        Command::Delete => Searchbar::delete,
+        Command::DeleteWord => Searchbar::delete_word,
+        Command::CursorLeft => Searchbar::left,
+        Command::CursorRight => Searchbar::right,
+        Command::CursorStart => Searchbar::home,
+        Command::CursorEnd => Searchbar::end,
+        Command::WordLeft => Searchbar::word_left,
+        Command::WordRight => Searchbar::word_right,
+        _ => return None,
+    };
+
+    Some(edit)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::path::Path;
+
+    use super::*;
+    use crate::{app::AppState, project::Project};
+
+    fn app() -> App {
+        let mut app =
+            App::new(["alpha", "beta", "gamma"].map(|name| Project::test(format!("/home/{name}"))));
+        app.set_vim(true);
+        app
+    }
+
+    fn press(app: &mut App, code: KeyCode) {
+        handle_key_events(KeyEvent::new(code, KeyModifiers::NONE), app).unwrap();
+    }
+
+    #[test]
+    fn escape_enters_normal_mode() {
+        let mut app = app();
+
+        press(&mut app, KeyCode::Esc);
+        assert_eq!(app.mode(), Mode::Normal);
+        assert!(app.state.is_running());
+
+        press(&mut app, KeyCode::Char('/'));
+        assert_eq!(app.mode(), Mode::Insert);
+
+        press(&mut app, KeyCode::Esc);
+        press(&mut app, KeyCode::Char('q'));
+        assert!(!app.state.is_running());
+    }
+
+    #[test]
+    fn ctrl_c_exits_insert_mode() {
+        let mut app = app();
+
+        handle_key_events(
+            KeyEvent::new(KeyCode::Char('c'), KeyModifiers::CONTROL),
+            &mut app,
+        )
+        .unwrap();
+        assert!(!app.state.is_running());
+    }
+
+    #[test]
+    fn normal_mode_navigation() {
+        let mut app = app();
+        press(&mut app, KeyCode::Esc);
+
+        press(&mut app, KeyCode::Char('G'));
+        assert_eq!(app.list_state.selected(), Some(2));
+        press(&mut app, KeyCode::Char('j'));
+        assert_eq!(app.list_state.selected(), Some(1));
+        press(&mut app, KeyCode::Char('g'));
+        assert_eq!(app.list_state.selected(), Some(1));
+        press(&mut app, KeyCode::Char('g'));
+        assert_eq!(app.list_state.selected(), Some(0));
+        press(&mut app, KeyCode::Char('k'));
+        assert_eq!(app.list_state.selected(), Some(1));
+    }
+
+    #[test]
+    fn marks_survive_filtering() {
+        let mut app = app();
+
+        press(&mut app, KeyCode::Tab);
+        app.paste("gamma");
+        press(&mut app, KeyCode::Tab);
+        app.clear_search();
+        press(&mut app, KeyCode::Enter);
+
+        let AppState::Selected(projects) = &app.state else {
+            panic!("expected projects to be selected");
+        };
+        let names: Vec<_> = projects.iter().map(Project::name).collect();
+        assert_eq!(names, ["alpha", "gamma"]);
+    }
+
+    #[test]
+    fn pinned_projects_first() {
+        let mut app = app();
+        app.set_vim(false);
+
+        app.last();
+        handle_key_events(
+            KeyEvent::new(KeyCode::Char('s'), KeyModifiers::ALT),
+            &mut app,
+        )
+        .unwrap();
+        assert_eq!(app.filtered_projects[0].name(), "gamma");
+        assert_eq!(app.list_state.selected(), Some(0));
+
+        app.paste("a");
+        assert_eq!(app.filtered_projects[0].name(), "gamma");
+    }
+
+    #[test]
+    fn nested_projects() {
+        let mut app = App::new(
+            [
+                "/home/mono",
+                "/home/mono/crates/core",
+                "/home/mono/crates/core/fuzz",
+                "/home/other",
+            ]
+            .map(Project::test),
+        );
+        let names = |app: &App| {
+            app.filtered_projects
+                .iter()
+                .map(Project::name)
+                .collect::<Vec<_>>()
+        };
+        assert_eq!(names(&app), ["mono", "other"]);
+
+        handle_key_events(
+            KeyEvent::new(KeyCode::Char('n'), KeyModifiers::ALT),
+            &mut app,
+        )
+        .unwrap();
+        assert_eq!(names(&app), ["mono", "core", "fuzz", "other"]);
+        assert_eq!(app.parent(&app.filtered_projects[0]), None);
+        assert_eq!(
+            app.parent(&app.filtered_projects[2]),
+            Some(Path::new("/home/mono/crates/core"))
+        );
+        assert_eq!(app.parent(&app.filtered_projects[3]), None);
+    }
+
+    #[test]
+    fn help() {
+        let mut app = app();
+
+        press(&mut app, KeyCode::F(1));
+        assert!(app.is_help_open());
+        press(&mut app, KeyCode::Enter);
+        assert!(app.state.is_running());
+        press(&mut app, KeyCode::Esc);
+        assert!(!app.is_help_open());
+
+        press(&mut app, KeyCode::Char('?'));
+        assert!(!app.is_help_open());
+
+        press(&mut app, KeyCode::Esc);
+        press(&mut app, KeyCode::Char('?'));
+        assert!(app.is_help_open());
+        press(&mut app, KeyCode::Char('q'));
+        assert!(!app.is_help_open());
+        assert!(app.state.is_running());
+    }
+
+    #[test]
+    fn escape_exits_without_vim() {
+        let mut app = app();
+        app.set_vim(false);
+
+        press(&mut app, KeyCode::Esc);
+
+        assert!(!app.state.is_running());
+    }
+}
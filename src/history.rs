@@ -0,0 +1,251 @@
+use std::{
+    collections::BTreeMap,
+    fs, io,
+    path::{Path, PathBuf},
+    time::{SystemTime, UNIX_EPOCH},
+};
+
+use crate::{app::AppResult, config::Config};
+
+/// Number of projects remembered, the least used ones being forgotten first.
+const MAX_ENTRIES: usize = 500;
+/// Total of the counts above which every count is reduced, so that old habits fade away.
+const MAX_TOTAL: f64 = 2000.;
+/// Factor applied to every count when [`MAX_TOTAL`] is reached.
+const AGING: f64 = 0.9;
+
+const HOUR: u64 = 60 * 60;
+const DAY: u64 = 24 * HOUR;
+const WEEK: u64 = 7 * DAY;
+
+/// How often and how recently each project was selected, to rank them by frecency.
+///
+/// The history is stored in `$XDG_STATE_HOME/fp/history`, one project per line.
+#[derive(Debug, Default)]
+pub struct History {
+    entries: BTreeMap<PathBuf, Entry>,
+    /// Current time, in seconds since the Unix epoch.
+    now: u64,
+    /// Whether the history changed since it was loaded.
+    changed: bool,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+struct Entry {
+    /// Number of selections, reduced as the history ages.
+    count: f64,
+    /// Time of the last selection, in seconds since the Unix epoch.
+    last: u64,
+}
+
+impl History {
+    /// Returns the path of the file holding the history.
+    pub fn file() -> Option<PathBuf> {
+        Config::state_dir().map(|dir| dir.join("history"))
+    }
+
+    /// Loads the history from the state file, a missing file meaning an empty history.
+    pub fn load() -> AppResult<Self> {
+        let now = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map_or(0, |duration| duration.as_secs());
+
+        let Some(path) = Self::file() else {
+            return Ok(Self::parse("", now));
+        };
+
+        match fs::read_to_string(&path) {
+            Ok(content) => Ok(Self::parse(&content, now)),
+            Err(err) if err.kind() == io::ErrorKind::NotFound => Ok(Self::parse("", now)),
+            Err(err) => Err(format!("cannot read '{}': {err}", path.display()).into()),
+        }
+    }
+
+    /// Parses the history, made of `<count>\t<last selection>\t<path>` lines.
+    ///
+    /// Invalid lines are ignored.
+    fn parse(content: &str, now: u64) -> Self {
+        let entries = content
+            .lines()
+            .filter_map(|line| {
+                let mut fields = line.splitn(3, '\t');
+                let count = fields.next()?.parse().ok()?;
+                let last = fields.next()?.parse().ok()?;
+                let path = PathBuf::from(fields.next()?);
+                Some((path, Entry { count, last }))
+            })
+            .collect();
+
+        Self {
+            entries,
+            now,
+            changed: false,
+        }
+    }
+
+    /// Writes the history to the state file if it changed.
+    pub fn save(&mut self) -> AppResult<()> {
+        if !self.changed {
+            return Ok(());
+        }
+
+        let path = Self::file().ok_or("cannot locate the state directory, HOME is not set")?;
+        if let Some(dir) = path.parent() {
+            fs::create_dir_all(dir)
+                .map_err(|err| format!("cannot create '{}': {err}", dir.display()))?;
+        }
+
+        let content: String = self
+            .entries
+            .iter()
+            .map(|(path, entry)| format!("{}\t{}\t{}\n", entry.count, entry.last, path.display()))
+            .collect();
+        fs::write(&path, content)
+            .map_err(|err| format!("cannot write '{}': {err}", path.display()))?;
+
+        self.changed = false;
+        Ok(())
+    }
+
+    /// Deletes the history file.
+    pub fn clear() -> AppResult<()> {
+        let Some(path) = Self::file() else {
+            return Ok(());
+        };
+
+        match fs::remove_file(&path) {
+            Err(err) if err.kind() != io::ErrorKind::NotFound => {
+                Err(format!("cannot remove '{}': {err}", path.display()).into())
+            }
+            _ => Ok(()),
+        }
+    }
+
+    /// Records the selection of a project.
+    ///
+    /// The recorded project is never forgotten right away, even if its count is the lowest.
+    pub fn record(&mut self, path: &Path) {
+        let entry = self.entries.entry(path.to_path_buf()).or_insert(Entry {
+            count: 0.,
+            last: self.now,
+        });
+        entry.count += 1.;
+        entry.last = self.now;
+        self.changed = true;
+
+        let total: f64 = self.entries.values().map(|entry| entry.count).sum();
+        if total > MAX_TOTAL {
+            for entry in self.entries.values_mut() {
+                entry.count *= AGING;
+            }
+            self.entries
+                .retain(|entry_path, entry| entry.count >= 1. || entry_path == path);
+        }
+
+        if self.entries.len() > MAX_ENTRIES {
+            let mut frecencies: Vec<_> = self
+                .entries
+                .keys()
+                .filter(|entry_path| *entry_path != path)
+                .map(|path| (self.frecency(path), path.clone()))
+                .collect();
+            frecencies.sort_by(|(a, _), (b, _)| a.total_cmp(b));
+
+            for (_, path) in frecencies
+                .into_iter()
+                .take(self.entries.len() - MAX_ENTRIES)
+            {
+                self.entries.remove(&path);
+            }
+        }
+    }
+
+    /// Returns the frecency of a project, higher being more frequently and recently used.
+    ///
+    /// Projects that were never selected have a frecency of 0.
+    pub fn frecency(&self, path: &Path) -> f64 {
+        let Some(entry) = self.entries.get(path) else {
+            return 0.;
+        };
+
+        let recency = match self.now.saturating_sub(entry.last) {
+            age if age < HOUR => 4.,
+            age if age < DAY => 2.,
+            age if age < WEEK => 0.5,
+            _ => 0.25,
+        };
+
+        entry.count * recency
+    }
+
+    /// Returns the factor dividing the search score of a project, at least 1.
+    ///
+    /// The boost grows slowly with the frecency, so that frequently used projects come first among
+    /// similar matches without hiding better matches.
+    pub fn boost(&self, path: &Path) -> f64 {
+        1. + 0.1 * self.frecency(path).ln_1p()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const NOW: u64 = 1_000_000_000;
+
+    #[test]
+    fn parse() {
+        let history = History::parse(
+            "3\t999999000\t/home/alpha\ninvalid\n1\t0\t/home/be\tta\n",
+            NOW,
+        );
+
+        assert_eq!(history.frecency(Path::new("/home/alpha")), 12.);
+        assert_eq!(history.frecency(Path::new("/home/be\tta")), 0.25);
+        assert_eq!(history.frecency(Path::new("/home/gamma")), 0.);
+    }
+
+    #[test]
+    fn record() {
+        let mut history = History::parse("", NOW);
+
+        history.record(Path::new("/home/alpha"));
+        history.record(Path::new("/home/alpha"));
+        history.record(Path::new("/home/beta"));
+
+        assert!(
+            history.frecency(Path::new("/home/alpha")) > history.frecency(Path::new("/home/beta"))
+        );
+        assert!(history.boost(Path::new("/home/alpha")) > 1.);
+        assert_eq!(history.boost(Path::new("/home/gamma")), 1.);
+    }
+
+    #[test]
+    fn aging() {
+        let mut history = History::parse("1999\t0\t/home/alpha\n1\t0\t/home/beta\n", NOW);
+
+        history.record(Path::new("/home/gamma"));
+
+        assert_eq!(
+            history.entries[Path::new("/home/alpha")].count,
+            1999. * AGING
+        );
+        assert!(!history.entries.contains_key(Path::new("/home/beta")));
+        assert!(history.entries.contains_key(Path::new("/home/gamma")));
+    }
+
+    #[test]
+    fn cap() {
+        let content: String = (0..MAX_ENTRIES)
+            .map(|i| format!("{}\t{NOW}\t/home/{i}\n", 2 + i % 2))
+            .collect();
+        let mut history = History::parse(&content, NOW);
+
+        history.record(Path::new("/home/new"));
+
+        assert_eq!(history.entries.len(), MAX_ENTRIES);
+        assert!(history.entries.contains_key(Path::new("/home/new")));
+        assert!(!history.entries.contains_key(Path::new("/home/0")));
+        assert!(history.entries.contains_key(Path::new("/home/1")));
+    }
+}
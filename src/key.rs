@@ -0,0 +1,258 @@
+use std::{fmt::Display, str::FromStr};
+
+use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
+use serde::{de, Deserialize, Deserializer};
+
+/// A key combination, such as `ctrl-e` or `alt-p`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct KeyChord {
+    pub modifiers: KeyModifiers,
+    pub code: KeyCode,
+}
+
+impl KeyChord {
+    pub const fn new(modifiers: KeyModifiers, code: KeyCode) -> Self {
+        Self { modifiers, code }
+    }
+
+    /// Returns whether the given key event triggers this chord.
+    pub fn matches(&self, event: &KeyEvent) -> bool {
+        *self == Self::from(*event)
+    }
+}
+
+impl From<KeyEvent> for KeyChord {
+    fn from(event: KeyEvent) -> Self {
+        let mut modifiers = event.modifiers;
+
+        // The shift modifier is already applied to characters and tab, `G` is sent as `shift-G`
+        // and `backtab` as `shift-backtab`
+        if let KeyCode::Char(_) | KeyCode::BackTab = event.code {
+            modifiers.remove(KeyModifiers::SHIFT);
+        }
+
+        Self::new(modifiers, event.code)
+    }
+}
+
+impl FromStr for KeyChord {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let s = s.trim();
+        let mut modifiers = KeyModifiers::NONE;
+        let mut rest = s;
+
+        // The key itself may be `-`, so only split on separators followed by something
+        while let Some((modifier, key)) = rest.split_once('-').filter(|(_, key)| !key.is_empty()) {
+            modifiers |= match modifier.to_lowercase().as_str() {
+                "ctrl" | "c" => KeyModifiers::CONTROL,
+                "alt" | "a" | "m" => KeyModifiers::ALT,
+                "shift" | "s" => KeyModifiers::SHIFT,
+                _ => return Err(format!("unknown modifier '{modifier}' in key '{s}'")),
+            };
+            rest = key;
+        }
+
+        let code = match rest.to_lowercase().as_str() {
+            "enter" | "return" => KeyCode::Enter,
+            "esc" | "escape" => KeyCode::Esc,
+            "tab" if modifiers.contains(KeyModifiers::SHIFT) => {
+                modifiers.remove(KeyModifiers::SHIFT);
+                KeyCode::BackTab
+            }
+            "tab" => KeyCode::Tab,
+            "backtab" => KeyCode::BackTab,
+            "backspace" | "bs" => KeyCode::Backspace,
+            "delete" | "del" => KeyCode::Delete,
+            "insert" | "ins" => KeyCode::Insert,
+            "up" => KeyCode::Up,
+            "down" => KeyCode::Down,
+            "left" => KeyCode::Left,
+            "right" => KeyCode::Right,
+            "home" => KeyCode::Home,
+            "end" => KeyCode::End,
+            "pageup" | "pgup" => KeyCode::PageUp,
+            "pagedown" | "pgdown" => KeyCode::PageDown,
+            "space" => KeyCode::Char(' '),
+            key if key.len() > 1 && key.starts_with('f') => key[1..]
+                .parse()
+                .ok()
+                .filter(|n| (1..=12).contains(n))
+                .map(KeyCode::F)
+                .ok_or_else(|| format!("unknown key '{rest}'"))?,
+            _ => {
+                let mut chars = rest.chars();
+                match (chars.next(), chars.next()) {
+                    (Some(c), None) if modifiers.contains(KeyModifiers::SHIFT) => {
+                        modifiers.remove(KeyModifiers::SHIFT);
+                        KeyCode::Char(c.to_ascii_uppercase())
+                    }
+                    (Some(c), None) => KeyCode::Char(c),
+                    _ => return Err(format!("unknown key '{rest}'")),
+                }
+            }
+        };
+
+        Ok(Self::new(modifiers, code))
+    }
+}
+
+impl Display for KeyChord {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        if self.modifiers.contains(KeyModifiers::CONTROL) {
+            f.write_str("ctrl-")?;
+        }
+        if self.modifiers.contains(KeyModifiers::ALT) {
+            f.write_str("alt-")?;
+        }
+        if self.modifiers.contains(KeyModifiers::SHIFT) {
+            f.write_str("shift-")?;
+        }
+
+        match self.code {
+            KeyCode::Enter => f.write_str("enter"),
+            KeyCode::Esc => f.write_str("esc"),
+            KeyCode::Tab => f.write_str("tab"),
+            KeyCode::BackTab => f.write_str("shift-tab"),
+            KeyCode::Backspace => f.write_str("backspace"),
+            KeyCode::Delete => f.write_str("delete"),
+            KeyCode::Insert => f.write_str("insert"),
+            KeyCode::Up => f.write_str("up"),
+            KeyCode::Down => f.write_str("down"),
+            KeyCode::Left => f.write_str("left"),
+            KeyCode::Right => f.write_str("right"),
+            KeyCode::Home => f.write_str("home"),
+            KeyCode::End => f.write_str("end"),
+            KeyCode::PageUp => f.write_str("pageup"),
+            KeyCode::PageDown => f.write_str("pagedown"),
+            KeyCode::Char(' ') => f.write_str("space"),
+            KeyCode::Char(c) => write!(f, "{c}"),
+            KeyCode::F(n) => write!(f, "f{n}"),
+            code => write!(f, "{code:?}"),
+        }
+    }
+}
+
+impl<'de> Deserialize<'de> for KeyChord {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        String::deserialize(deserializer)?
+            .parse()
+            .map_err(de::Error::custom)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn chord(modifiers: KeyModifiers, code: KeyCode) -> KeyChord {
+        KeyChord::new(modifiers, code)
+    }
+
+    #[test]
+    fn parse_char() {
+        assert_eq!(
+            "e".parse::<KeyChord>().unwrap(),
+            chord(KeyModifiers::NONE, KeyCode::Char('e'))
+        );
+        assert_eq!(
+            "G".parse::<KeyChord>().unwrap(),
+            chord(KeyModifiers::NONE, KeyCode::Char('G'))
+        );
+        assert_eq!(
+            "shift-g".parse::<KeyChord>().unwrap(),
+            chord(KeyModifiers::NONE, KeyCode::Char('G'))
+        );
+        assert_eq!(
+            "-".parse::<KeyChord>().unwrap(),
+            chord(KeyModifiers::NONE, KeyCode::Char('-'))
+        );
+        assert_eq!(
+            "space".parse::<KeyChord>().unwrap(),
+            chord(KeyModifiers::NONE, KeyCode::Char(' '))
+        );
+    }
+
+    #[test]
+    fn parse_modifiers() {
+        assert_eq!(
+            "ctrl-e".parse::<KeyChord>().unwrap(),
+            chord(KeyModifiers::CONTROL, KeyCode::Char('e'))
+        );
+        assert_eq!(
+            " Alt-P ".parse::<KeyChord>().unwrap(),
+            chord(KeyModifiers::ALT, KeyCode::Char('P'))
+        );
+        assert_eq!(
+            "ctrl-alt-x".parse::<KeyChord>().unwrap(),
+            chord(
+                KeyModifiers::CONTROL | KeyModifiers::ALT,
+                KeyCode::Char('x')
+            )
+        );
+        assert_eq!(
+            "ctrl--".parse::<KeyChord>().unwrap(),
+            chord(KeyModifiers::CONTROL, KeyCode::Char('-'))
+        );
+    }
+
+    #[test]
+    fn parse_named_keys() {
+        assert_eq!(
+            "enter".parse::<KeyChord>().unwrap(),
+            chord(KeyModifiers::NONE, KeyCode::Enter)
+        );
+        assert_eq!(
+            "shift-tab".parse::<KeyChord>().unwrap(),
+            chord(KeyModifiers::NONE, KeyCode::BackTab)
+        );
+        assert_eq!(
+            "PageDown".parse::<KeyChord>().unwrap(),
+            chord(KeyModifiers::NONE, KeyCode::PageDown)
+        );
+        assert_eq!(
+            "f1".parse::<KeyChord>().unwrap(),
+            chord(KeyModifiers::NONE, KeyCode::F(1))
+        );
+    }
+
+    #[test]
+    fn parse_errors() {
+        assert!("".parse::<KeyChord>().is_err());
+        assert!("hyper-e".parse::<KeyChord>().is_err());
+        assert!("ctrl-foo".parse::<KeyChord>().is_err());
+        assert!("f13".parse::<KeyChord>().is_err());
+    }
+
+    #[test]
+    fn display_roundtrip() {
+        for key in [
+            "ctrl-e",
+            "alt-p",
+            "enter",
+            "shift-tab",
+            "G",
+            "f1",
+            "ctrl-alt-x",
+            "space",
+        ] {
+            assert_eq!(key.parse::<KeyChord>().unwrap().to_string(), key);
+        }
+    }
+
+    #[test]
+    fn matches_event() {
+        let chord = "G".parse::<KeyChord>().unwrap();
+
+        assert!(chord.matches(&KeyEvent::new(KeyCode::Char('G'), KeyModifiers::SHIFT)));
+        assert!(!chord.matches(&KeyEvent::new(KeyCode::Char('g'), KeyModifiers::NONE)));
+
+        let chord = "shift-tab".parse::<KeyChord>().unwrap();
+
+        assert!(chord.matches(&KeyEvent::new(KeyCode::BackTab, KeyModifiers::SHIFT)));
+    }
+}
@@ -0,0 +1,550 @@
+use std::{
+    collections::{BTreeMap, HashMap},
+    fmt::Display,
+    str::FromStr,
+};
+
+use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
+use serde::Deserialize;
+
+use crate::{action::Action, app::AppResult, key::KeyChord};
+
+/// A command that can be bound to a key.
+#[derive(Clone, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum Command {
+    /// Exit without selecting anything, or close the current popup.
+    Cancel,
+    /// Select the highlighted project, or run the highlighted action.
+    Accept,
+    Up,
+    Down,
+    /// Move up by the height of the list.
+    PageUp,
+    /// Move down by the height of the list.
+    PageDown,
+    /// Select the first result, the best match.
+    First,
+    /// Select the last result.
+    Last,
+    /// Clear the search.
+    Clear,
+    /// Delete the character before the cursor.
+    Backspace,
+    /// Delete the character under the cursor.
+    Delete,
+    /// Delete the word before the cursor.
+    DeleteWord,
+    CursorLeft,
+    CursorRight,
+    /// Move the cursor to the start of the search.
+    CursorStart,
+    /// Move the cursor to the end of the search.
+    CursorEnd,
+    /// Move the cursor to the previous word.
+    WordLeft,
+    /// Move the cursor to the next word.
+    WordRight,
+    TogglePreview,
+    /// Mark or unmark the highlighted project.
+    ToggleMark,
+    /// Unmark the highlighted project.
+    Unmark,
+    /// Pin or unpin the highlighted project.
+    TogglePin,
+    /// Open or close the action menu.
+    ActionMenu,
+    /// Show or hide the key bindings.
+    Help,
+    /// Switch to the next built-in theme.
+    CycleTheme,
+    /// Switch to the next order of the project list.
+    CycleSort,
+    /// List or hide the projects nested in another project.
+    ToggleNested,
+    /// Run the action of the given name on the highlighted project.
+    Action(String),
+}
+
+impl Command {
+    /// Commands that are not actions, in the order they are displayed.
+    pub const BUILTINS: [Self; 27] = [
+        Self::Cancel,
+        Self::Accept,
+        Self::Up,
+        Self::Down,
+        Self::PageUp,
+        Self::PageDown,
+        Self::First,
+        Self::Last,
+        Self::Clear,
+        Self::Backspace,
+        Self::Delete,
+        Self::DeleteWord,
+        Self::CursorLeft,
+        Self::CursorRight,
+        Self::CursorStart,
+        Self::CursorEnd,
+        Self::WordLeft,
+        Self::WordRight,
+        Self::TogglePreview,
+        Self::ToggleMark,
+        Self::Unmark,
+        Self::TogglePin,
+        Self::ActionMenu,
+        Self::Help,
+        Self::CycleTheme,
+        Self::CycleSort,
+        Self::ToggleNested,
+    ];
+
+    /// Returns a short description of what the command does, displayed in the help.
+    pub fn description(&self) -> &'static str {
+        match self {
+            Self::Cancel => "exit without selecting anything, close a popup",
+            Self::Accept => "select the highlighted project",
+            Self::Up => "move up",
+            Self::Down => "move down",
+            Self::PageUp => "move up by the height of the list",
+            Self::PageDown => "move down by the height of the list",
+            Self::First => "select the first result, the best match",
+            Self::Last => "select the last result",
+            Self::Clear => "clear the search",
+            Self::Backspace => "delete the character before the cursor",
+            Self::Delete => "delete the character under the cursor",
+            Self::DeleteWord => "delete the word before the cursor",
+            Self::CursorLeft => "move the cursor left",
+            Self::CursorRight => "move the cursor right",
+            Self::CursorStart => "move the cursor to the start of the search",
+            Self::CursorEnd => "move the cursor to the end of the search",
+            Self::WordLeft => "move the cursor to the previous word",
+            Self::WordRight => "move the cursor to the next word",
+            Self::TogglePreview => "show or hide the preview",
+            Self::ToggleMark => "mark or unmark the highlighted project",
+            Self::Unmark => "unmark the highlighted project",
+            Self::TogglePin => "pin or unpin the highlighted project",
+            Self::ActionMenu => "open the action menu",
+            Self::Help => "show or hide the key bindings",
+            Self::CycleTheme => "preview the next built-in theme",
+            Self::CycleSort => "switch to the next sort order",
+            Self::ToggleNested => "show or hide the nested projects",
+            Self::Action(_) => "run the action",
+        }
+    }
+
+    /// Returns the keys bound to this command when nothing is configured.
+    fn default_keys(&self) -> Vec<KeyChord> {
+        let key = KeyChord::new;
+        let ctrl = |c| KeyChord::new(KeyModifiers::CONTROL, KeyCode::Char(c));
+        let alt = |c| KeyChord::new(KeyModifiers::ALT, KeyCode::Char(c));
+
+        match self {
+            Self::Cancel => vec![ctrl('c'), key(KeyModifiers::NONE, KeyCode::Esc)],
+            Self::Accept => vec![key(KeyModifiers::NONE, KeyCode::Enter)],
+            Self::Up => vec![ctrl('k'), ctrl('p'), key(KeyModifiers::NONE, KeyCode::Up)],
+            Self::Down => vec![ctrl('j'), ctrl('n'), key(KeyModifiers::NONE, KeyCode::Down)],
+            Self::PageUp => vec![key(KeyModifiers::NONE, KeyCode::PageUp)],
+            Self::PageDown => vec![key(KeyModifiers::NONE, KeyCode::PageDown)],
+            Self::First => vec![key(KeyModifiers::NONE, KeyCode::Home), alt('g')],
+            Self::Last => vec![key(KeyModifiers::NONE, KeyCode::End), alt('G')],
+            Self::Clear => vec![ctrl('u')],
+            Self::Backspace => vec![key(KeyModifiers::NONE, KeyCode::Backspace)],
+            Self::Delete => vec![key(KeyModifiers::NONE, KeyCode::Delete)],
+            Self::DeleteWord => vec![ctrl('w')],
+            Self::CursorLeft => vec![key(KeyModifiers::NONE, KeyCode::Left)],
+            Self::CursorRight => vec![key(KeyModifiers::NONE, KeyCode::Right)],
+            Self::CursorStart => vec![ctrl('a')],
+            Self::CursorEnd => vec![ctrl('e')],
+            Self::WordLeft => vec![alt('b')],
+            Self::WordRight => vec![alt('f')],
+            Self::TogglePreview => vec![alt('p')],
+            Self::ToggleMark => vec![key(KeyModifiers::NONE, KeyCode::Tab)],
+            Self::Unmark => vec![key(KeyModifiers::NONE, KeyCode::BackTab)],
+            Self::TogglePin => vec![alt('s')],
+            Self::ActionMenu => vec![ctrl('o')],
+            Self::Help => vec![key(KeyModifiers::NONE, KeyCode::F(1))],
+            Self::CycleTheme => vec![alt('t')],
+            Self::CycleSort => vec![alt('o')],
+            Self::ToggleNested => vec![alt('n')],
+            Self::Action(_) => vec![],
+        }
+    }
+}
+
+impl FromStr for Command {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        if let Some(action) = s.strip_prefix("action:") {
+            return Ok(Self::Action(action.to_string()));
+        }
+
+        Self::BUILTINS
+            .into_iter()
+            .find(|command| command.to_string() == s)
+            .ok_or_else(|| format!("unknown command '{s}'"))
+    }
+}
+
+impl Display for Command {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let name = match self {
+            Self::Cancel => "cancel",
+            Self::Accept => "accept",
+            Self::Up => "up",
+            Self::Down => "down",
+            Self::PageUp => "page-up",
+            Self::PageDown => "page-down",
+            Self::First => "first",
+            Self::Last => "last",
+            Self::Clear => "clear",
+            Self::Backspace => "backspace",
+            Self::Delete => "delete",
+            Self::DeleteWord => "delete-word",
+            Self::CursorLeft => "cursor-left",
+            Self::CursorRight => "cursor-right",
+            Self::CursorStart => "cursor-start",
+            Self::CursorEnd => "cursor-end",
+            Self::WordLeft => "word-left",
+            Self::WordRight => "word-right",
+            Self::TogglePreview => "toggle-preview",
+            Self::ToggleMark => "toggle-mark",
+            Self::Unmark => "unmark",
+            Self::TogglePin => "toggle-pin",
+            Self::ActionMenu => "action-menu",
+            Self::Help => "help",
+            Self::CycleTheme => "cycle-theme",
+            Self::CycleSort => "cycle-sort",
+            Self::ToggleNested => "toggle-nested",
+            Self::Action(action) => return write!(f, "action:{action}"),
+        };
+
+        f.write_str(name)
+    }
+}
+
+/// A command of the vim normal mode.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum NormalCommand {
+    /// Run the command, like its keys in insert mode.
+    Run(Command),
+    /// Go back to insert mode.
+    Insert,
+}
+
+impl NormalCommand {
+    pub fn description(&self) -> &'static str {
+        match self {
+            Self::Run(command) => command.description(),
+            Self::Insert => "go back to insert mode",
+        }
+    }
+}
+
+impl Display for NormalCommand {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Run(command) => command.fmt(f),
+            Self::Insert => f.write_str("insert"),
+        }
+    }
+}
+
+/// Returns the key of a character without modifiers, for the normal mode bindings.
+const fn plain(c: char) -> KeyChord {
+    KeyChord::new(KeyModifiers::NONE, KeyCode::Char(c))
+}
+
+/// Key sequences of the vim normal mode, in the order they are displayed.
+///
+/// Keys that are not listed run the command they are bound to in the keymap, unless it edits the
+/// search.
+pub static NORMAL_MODE: [(&[KeyChord], NormalCommand); 12] = [
+    (&[plain('j')], NormalCommand::Run(Command::Down)),
+    (&[plain('k')], NormalCommand::Run(Command::Up)),
+    (
+        &[KeyChord::new(KeyModifiers::CONTROL, KeyCode::Char('d'))],
+        NormalCommand::Run(Command::PageDown),
+    ),
+    (
+        &[KeyChord::new(KeyModifiers::CONTROL, KeyCode::Char('u'))],
+        NormalCommand::Run(Command::PageUp),
+    ),
+    (
+        &[plain('g'), plain('g')],
+        NormalCommand::Run(Command::First),
+    ),
+    (&[plain('G')], NormalCommand::Run(Command::Last)),
+    (&[plain('p')], NormalCommand::Run(Command::TogglePreview)),
+    (&[plain('?')], NormalCommand::Run(Command::Help)),
+    (&[plain('q')], NormalCommand::Run(Command::Cancel)),
+    (&[plain('/')], NormalCommand::Insert),
+    (&[plain('i')], NormalCommand::Insert),
+    (&[plain('a')], NormalCommand::Insert),
+];
+
+/// Returns the normal mode command bound to the key sequence.
+pub fn normal_command(keys: &[KeyChord]) -> Option<&'static NormalCommand> {
+    NORMAL_MODE
+        .iter()
+        .find(|(bound, _)| *bound == keys)
+        .map(|(_, command)| command)
+}
+
+/// One or more keys bound to a command in the configuration.
+#[derive(Clone, Debug, PartialEq, Eq, Deserialize)]
+#[serde(untagged)]
+pub enum Keys {
+    One(KeyChord),
+    Many(Vec<KeyChord>),
+}
+
+impl Keys {
+    fn into_vec(self) -> Vec<KeyChord> {
+        match self {
+            Self::One(key) => vec![key],
+            Self::Many(keys) => keys,
+        }
+    }
+}
+
+/// Maps key chords to commands.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Keymap {
+    bindings: HashMap<KeyChord, Command>,
+}
+
+impl Default for Keymap {
+    fn default() -> Self {
+        Self::new(&BTreeMap::new(), &[]).expect("default keymap should not have conflicts")
+    }
+}
+
+impl Keymap {
+    /// Creates a keymap from the configured keys and actions.
+    ///
+    /// Configuring a command replaces its default keys, including the `key` of an action bound
+    /// with `action:<name>`. Binding a key to multiple commands is an error, whether the keys are
+    /// configured or the default keys of the other commands.
+    pub fn new(keys: &BTreeMap<String, Keys>, actions: &[Action]) -> AppResult<Self> {
+        let configured = keys
+            .iter()
+            .map(|(command, keys)| Ok((command.parse()?, keys.clone().into_vec())))
+            .collect::<Result<Vec<(Command, _)>, String>>()?;
+
+        let action_keys = actions.iter().filter_map(|action| {
+            let command = Command::Action(action.name.clone());
+            if configured
+                .iter()
+                .any(|(configured, _)| *configured == command)
+            {
+                return None;
+            }
+            Some((command, vec![action.key?]))
+        });
+
+        let defaults = Command::BUILTINS
+            .into_iter()
+            .filter(|command| {
+                !configured
+                    .iter()
+                    .any(|(configured, _)| configured == command)
+            })
+            .map(|command| {
+                let keys = command.default_keys();
+                (command, keys)
+            })
+            .collect::<Vec<_>>();
+
+        let mut bindings = HashMap::new();
+
+        for (command, keys) in configured
+            .iter()
+            .cloned()
+            .chain(action_keys)
+            .chain(defaults)
+        {
+            if let Command::Action(name) = &command {
+                if !actions.iter().any(|action| &action.name == name) {
+                    return Err(format!("unknown action '{name}' in key bindings").into());
+                }
+            }
+
+            for key in keys {
+                if let Some(other) = bindings.insert(key, command.clone()) {
+                    if other != command {
+                        return Err(format!(
+                            "key '{key}' is bound to both '{other}' and '{command}'"
+                        )
+                        .into());
+                    }
+                }
+            }
+        }
+
+        Ok(Self { bindings })
+    }
+
+    /// Returns the command bound to the given key event.
+    pub fn get(&self, event: &KeyEvent) -> Option<&Command> {
+        self.bindings.get(&KeyChord::from(*event))
+    }
+
+    /// Returns the keys bound to the given command.
+    pub fn keys_for(&self, command: &Command) -> Vec<KeyChord> {
+        let mut keys: Vec<_> = self
+            .bindings
+            .iter()
+            .filter(|(_, bound)| *bound == command)
+            .map(|(key, _)| *key)
+            .collect();
+        keys.sort_by_key(KeyChord::to_string);
+        keys
+    }
+
+    /// Returns every binding, sorted by command.
+    pub fn bindings(&self) -> Vec<(&KeyChord, &Command)> {
+        let mut bindings: Vec<_> = self.bindings.iter().collect();
+        bindings.sort_by_key(|(key, command)| (*command, key.to_string()));
+        bindings
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::action::Operation;
+
+    fn keys(config: &[(&str, &[&str])]) -> BTreeMap<String, Keys> {
+        config
+            .iter()
+            .map(|(command, keys)| {
+                let keys = keys.iter().map(|key| key.parse().unwrap()).collect();
+                (command.to_string(), Keys::Many(keys))
+            })
+            .collect()
+    }
+
+    fn action(name: &str, key: Option<&str>) -> Action {
+        Action {
+            name: name.to_string(),
+            description: None,
+            operation: Operation::Command("true".to_string()),
+            key: key.map(|key| key.parse().unwrap()),
+        }
+    }
+
+    fn get(keymap: &Keymap, key: &str) -> Option<Command> {
+        let key: KeyChord = key.parse().unwrap();
+        keymap.get(&KeyEvent::new(key.code, key.modifiers)).cloned()
+    }
+
+    #[test]
+    fn command_names() {
+        for command in Command::BUILTINS {
+            assert_eq!(command.to_string().parse::<Command>().unwrap(), command);
+        }
+        assert_eq!(
+            "action:edit".parse::<Command>().unwrap(),
+            Command::Action("edit".to_string())
+        );
+        assert!("foo".parse::<Command>().is_err());
+    }
+
+    #[test]
+    fn defaults() {
+        let keymap = Keymap::default();
+
+        assert_eq!(get(&keymap, "esc"), Some(Command::Cancel));
+        assert_eq!(get(&keymap, "ctrl-c"), Some(Command::Cancel));
+        assert_eq!(get(&keymap, "ctrl-k"), Some(Command::Up));
+        assert_eq!(get(&keymap, "down"), Some(Command::Down));
+        assert_eq!(get(&keymap, "home"), Some(Command::First));
+        assert_eq!(get(&keymap, "ctrl-a"), Some(Command::CursorStart));
+        assert_eq!(get(&keymap, "alt-G"), Some(Command::Last));
+        assert_eq!(get(&keymap, "alt-p"), Some(Command::TogglePreview));
+        assert_eq!(get(&keymap, "a"), None);
+    }
+
+    #[test]
+    fn configured_keys_replace_defaults() {
+        let keymap = Keymap::new(&keys(&[("up", &["up", "ctrl-p"])]), &[]).unwrap();
+
+        assert_eq!(get(&keymap, "up"), Some(Command::Up));
+        assert_eq!(get(&keymap, "ctrl-p"), Some(Command::Up));
+        assert_eq!(get(&keymap, "ctrl-k"), None);
+    }
+
+    #[test]
+    fn configured_keys_conflict_with_defaults() {
+        let err = Keymap::new(&keys(&[("clear", &["ctrl-k"])]), &[]).unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "key 'ctrl-k' is bound to both 'clear' and 'up'"
+        );
+
+        let keymap = Keymap::new(&keys(&[("clear", &["ctrl-k"]), ("up", &["up"])]), &[]).unwrap();
+        assert_eq!(get(&keymap, "ctrl-k"), Some(Command::Clear));
+        assert_eq!(get(&keymap, "ctrl-u"), None);
+    }
+
+    #[test]
+    fn unbind() {
+        let keymap = Keymap::new(&keys(&[("toggle-preview", &[])]), &[]).unwrap();
+
+        assert_eq!(get(&keymap, "alt-p"), None);
+    }
+
+    #[test]
+    fn actions() {
+        let actions = [action("edit", Some("alt-e")), action("copy", None)];
+        let keymap = Keymap::new(&keys(&[("action:copy", &["ctrl-y"])]), &actions).unwrap();
+
+        assert_eq!(
+            get(&keymap, "alt-e"),
+            Some(Command::Action("edit".to_string()))
+        );
+        assert_eq!(
+            get(&keymap, "ctrl-y"),
+            Some(Command::Action("copy".to_string()))
+        );
+
+        let keymap = Keymap::new(&keys(&[("action:edit", &["ctrl-x"])]), &actions).unwrap();
+        assert_eq!(get(&keymap, "alt-e"), None);
+        assert_eq!(
+            get(&keymap, "ctrl-x"),
+            Some(Command::Action("edit".to_string()))
+        );
+    }
+
+    #[test]
+    fn conflicts() {
+        let err =
+            Keymap::new(&keys(&[("clear", &["ctrl-x"]), ("up", &["ctrl-x"])]), &[]).unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "key 'ctrl-x' is bound to both 'clear' and 'up'"
+        );
+
+        let err = Keymap::new(
+            &keys(&[("up", &["ctrl-e"])]),
+            &[action("edit", Some("ctrl-e"))],
+        )
+        .unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "key 'ctrl-e' is bound to both 'up' and 'action:edit'"
+        );
+
+        let err = Keymap::new(&BTreeMap::new(), &[action("edit", Some("ctrl-e"))]).unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "key 'ctrl-e' is bound to both 'action:edit' and 'cursor-end'"
+        );
+    }
+
+    #[test]
+    fn unknown_names() {
+        assert!(Keymap::new(&keys(&[("foo", &["ctrl-x"])]), &[]).is_err());
+        assert!(Keymap::new(&keys(&[("action:foo", &["ctrl-x"])]), &[]).is_err());
+    }
+}
@@ -0,0 +1,210 @@
+use ratatui::{
+    layout::{self, Constraint, Direction, Rect},
+    widgets::ListDirection,
+};
+use serde::Deserialize;
+
+/// Where the preview is displayed, relative to the projects.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum PreviewPosition {
+    #[default]
+    Right,
+    Left,
+    Top,
+    Bottom,
+}
+
+/// Where the search bar is displayed, relative to the project list.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum SearchbarPosition {
+    Top,
+    #[default]
+    Bottom,
+}
+
+/// Order in which the projects are displayed, the best match being first.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum ListOrder {
+    /// The best match is at the bottom, next to the search bar.
+    #[default]
+    BottomToTop,
+    /// The best match is at the top.
+    TopToBottom,
+}
+
+impl ListOrder {
+    /// Returns the change of index moving the selection one line up on screen.
+    pub fn up(self) -> isize {
+        match self {
+            Self::BottomToTop => 1,
+            Self::TopToBottom => -1,
+        }
+    }
+}
+
+impl From<ListOrder> for ListDirection {
+    fn from(order: ListOrder) -> Self {
+        match order {
+            ListOrder::BottomToTop => Self::BottomToTop,
+            ListOrder::TopToBottom => Self::TopToBottom,
+        }
+    }
+}
+
+/// A percentage between 1 and 99.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Deserialize)]
+#[serde(try_from = "u16")]
+pub struct Percentage(u16);
+
+impl TryFrom<u16> for Percentage {
+    type Error = String;
+
+    fn try_from(value: u16) -> Result<Self, Self::Error> {
+        if (1..100).contains(&value) {
+            Ok(Self(value))
+        } else {
+            Err(format!("invalid percentage {value}, expected 1 to 99"))
+        }
+    }
+}
+
+/// Layout of the interface, from the `[layout]` section of the configuration.
+#[derive(Clone, Debug, PartialEq, Eq, Deserialize)]
+#[serde(default, deny_unknown_fields, rename_all = "kebab-case")]
+pub struct Layout {
+    pub preview: PreviewPosition,
+    /// Size of the preview, in percent of the terminal width or height.
+    pub preview_size: Percentage,
+    /// Terminal width up to which the preview is hidden, when on the left or right.
+    pub preview_min_width: u16,
+    /// Terminal height up to which the preview is hidden, when on the top or bottom.
+    pub preview_min_height: u16,
+    pub searchbar: SearchbarPosition,
+    pub order: ListOrder,
+}
+
+impl Default for Layout {
+    fn default() -> Self {
+        Self {
+            preview: PreviewPosition::default(),
+            preview_size: Percentage(30),
+            preview_min_width: 100,
+            preview_min_height: 30,
+            searchbar: SearchbarPosition::default(),
+            order: ListOrder::default(),
+        }
+    }
+}
+
+impl Layout {
+    /// Splits the area between the projects and the preview.
+    ///
+    /// The preview area is `None` if it is hidden or the area is too small for it.
+    pub fn split(&self, area: Rect, show_preview: bool) -> (Rect, Option<Rect>) {
+        let (direction, fits) = match self.preview {
+            PreviewPosition::Left | PreviewPosition::Right => {
+                (Direction::Horizontal, area.width > self.preview_min_width)
+            }
+            PreviewPosition::Top | PreviewPosition::Bottom => {
+                (Direction::Vertical, area.height > self.preview_min_height)
+            }
+        };
+
+        if !show_preview || !fits {
+            return (area, None);
+        }
+
+        let preview = Constraint::Percentage(self.preview_size.0);
+        let [first, second] = match self.preview {
+            PreviewPosition::Left | PreviewPosition::Top => {
+                layout::Layout::new(direction, [preview, Constraint::Min(0)]).areas(area)
+            }
+            PreviewPosition::Right | PreviewPosition::Bottom => {
+                layout::Layout::new(direction, [Constraint::Min(0), preview]).areas(area)
+            }
+        };
+
+        match self.preview {
+            PreviewPosition::Left | PreviewPosition::Top => (second, Some(first)),
+            PreviewPosition::Right | PreviewPosition::Bottom => (first, Some(second)),
+        }
+    }
+
+    /// Splits the projects area between the list and the search bar, returned in that order.
+    pub fn split_projects(&self, area: Rect) -> (Rect, Rect) {
+        let list = Constraint::Min(3);
+        let searchbar = Constraint::Length(3);
+
+        match self.searchbar {
+            SearchbarPosition::Top => {
+                let [searchbar, list] = layout::Layout::vertical([searchbar, list]).areas(area);
+                (list, searchbar)
+            }
+            SearchbarPosition::Bottom => {
+                let [list, searchbar] = layout::Layout::vertical([list, searchbar]).areas(area);
+                (list, searchbar)
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const AREA: Rect = Rect {
+        x: 0,
+        y: 0,
+        width: 120,
+        height: 40,
+    };
+
+    #[test]
+    fn default_split() {
+        let layout = Layout::default();
+
+        let (projects, preview) = layout.split(AREA, true);
+        assert_eq!(projects, Rect::new(0, 0, 84, 40));
+        assert_eq!(preview, Some(Rect::new(84, 0, 36, 40)));
+
+        assert_eq!(layout.split(AREA, false), (AREA, None));
+        assert_eq!(layout.split(Rect::new(0, 0, 100, 40), true).1, None);
+    }
+
+    #[test]
+    fn preview_on_top() {
+        let layout = Layout {
+            preview: PreviewPosition::Top,
+            preview_size: Percentage(50),
+            ..Default::default()
+        };
+
+        let (projects, preview) = layout.split(AREA, true);
+        assert_eq!(projects, Rect::new(0, 20, 120, 20));
+        assert_eq!(preview, Some(Rect::new(0, 0, 120, 20)));
+
+        assert_eq!(layout.split(Rect::new(0, 0, 120, 20), true).1, None);
+    }
+
+    #[test]
+    fn searchbar_on_top() {
+        let layout = Layout {
+            searchbar: SearchbarPosition::Top,
+            ..Default::default()
+        };
+
+        let (list, searchbar) = layout.split_projects(AREA);
+        assert_eq!(list, Rect::new(0, 3, 120, 37));
+        assert_eq!(searchbar, Rect::new(0, 0, 120, 3));
+    }
+
+    #[test]
+    fn percentage() {
+        assert!(Percentage::try_from(0).is_err());
+        assert!(Percentage::try_from(50).is_ok());
+        assert!(Percentage::try_from(100).is_err());
+    }
+}
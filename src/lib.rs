@@ -1,11 +1,29 @@
+/// Post-selection actions.
+pub mod action;
 /// Application.
 pub mod app;
 pub mod cli;
+/// User configuration.
+pub mod config;
 /// Terminal events handler.
 pub mod event;
 /// Event handler.
 pub mod handler;
+/// Frecency of the selected projects.
+pub mod history;
+/// Key chords.
+pub mod key;
+/// Key bindings.
+pub mod keymap;
+/// Layout of the interface.
+pub mod layout;
+/// Pinned projects.
+pub mod pins;
 pub mod project;
+/// Background project discovery.
+pub mod scanner;
+/// Shell integration scripts.
+pub mod shell;
 pub mod sorter;
 pub mod theme;
 /// Terminal user interface.
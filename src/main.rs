@@ -1,20 +1,162 @@
-use std::{env, io, path::PathBuf, time::Instant};
+use std::{
+    env,
+    io::{self, Write},
+    path::{Path, PathBuf},
+    process::ExitCode,
+};
 
+use argh::FromArgs;
 use fp::{
-    app::{App, AppResult},
-    cli::Cli,
+    app::{App, AppResult, AppState},
+    cli::{Cli, Command, HistoryCommand},
+    config::Config,
     event::{Event, EventHandler},
-    handler::handle_key_events,
-    project::Project,
-    theme::init_theme,
+    handler::{handle_key_events, handle_mouse_events},
+    history::History,
+    pins::Pins,
+    scanner,
+    theme::{init_theme, ColorSupport, Theme},
     tui::Tui,
 };
-use globwalk::GlobWalkerBuilder;
-use ratatui::{backend::CrosstermBackend, Terminal};
+use ratatui::{backend::CrosstermBackend, Terminal, TerminalOptions, Viewport};
+
+/// Exit code used when an error occurred.
+const EXIT_ERROR: u8 = 2;
+
+fn main() -> ExitCode {
+    let cli = match parse_args() {
+        Ok(cli) => cli,
+        Err(code) => return code,
+    };
+
+    match run(cli) {
+        Ok(code) => code,
+        Err(err) => {
+            eprintln!("Error: {err}");
+            ExitCode::from(EXIT_ERROR)
+        }
+    }
+}
+
+/// Parses the command line arguments, printing the help or the parsing error if needed.
+///
+/// Unlike [`argh::from_env`], invalid arguments exit with [`EXIT_ERROR`] like any other error.
+fn parse_args() -> Result<Cli, ExitCode> {
+    let args = env::args_os()
+        .map(|arg| arg.into_string())
+        .collect::<Result<Vec<_>, _>>()
+        .map_err(|arg| {
+            eprintln!("Error: invalid UTF-8 argument {}", arg.to_string_lossy());
+            ExitCode::from(EXIT_ERROR)
+        })?;
+    let command = args
+        .first()
+        .and_then(|path| Path::new(path).file_name())
+        .and_then(|name| name.to_str())
+        .unwrap_or("fp");
+    let args = split_options(args.iter().skip(1).map(String::as_str));
+
+    Cli::from_args(&[command], &args).map_err(|early_exit| match early_exit.status {
+        Ok(()) => {
+            println!("{}", early_exit.output);
+            ExitCode::SUCCESS
+        }
+        Err(()) => {
+            eprintln!(
+                "{}\nRun {command} --help for more information.",
+                early_exit.output
+            );
+            ExitCode::from(EXIT_ERROR)
+        }
+    })
+}
+
+/// Splits the options given as `--option=value` in two arguments, which argh does not support.
+///
+/// Arguments following `--` are left as is.
+fn split_options<'a>(args: impl Iterator<Item = &'a str>) -> Vec<&'a str> {
+    let mut split = Vec::new();
+    let mut options = true;
 
-fn main() -> AppResult<()> {
-    let cli: Cli = argh::from_env();
-    init_theme(cli.theme);
+    for arg in args {
+        options &= arg != "--";
+
+        match arg.split_once('=') {
+            Some((option, value)) if options && option.starts_with("--") => {
+                split.extend([option, value])
+            }
+            _ => split.push(arg),
+        }
+    }
+    split
+}
+
+fn run(cli: Cli) -> AppResult<ExitCode> {
+    if let Some(Command::Init(init)) = cli.command {
+        print!("{}", init.shell.init_script(!init.no_key_binding));
+        return Ok(ExitCode::SUCCESS);
+    }
+
+    let config = Config::load(cli.config.as_deref())?;
+    let keymap = config.keymap()?;
+
+    if let Some(Command::Themes(_)) = cli.command {
+        // `auto` is not cycled through by the preview, it depends on the terminal
+        for name in ["auto"].into_iter().chain(Theme::builtin_names()) {
+            println!("{name}");
+        }
+        for name in Theme::user_names() {
+            println!("{name}");
+        }
+        return Ok(ExitCode::SUCCESS);
+    }
+
+    if let Some(Command::Pin(pin)) = &cli.command {
+        let mut pins = Pins::load()?;
+        let Some(path) = &pin.path else {
+            for path in pins.iter() {
+                println!("{}", path.display());
+            }
+            return Ok(ExitCode::SUCCESS);
+        };
+
+        if pin.remove {
+            // The directory may no longer exist
+            let path = path.canonicalize().unwrap_or_else(|_| path.clone());
+            pins.remove(&path);
+        } else {
+            let path = path
+                .canonicalize()
+                .map_err(|err| format!("cannot pin '{}': {err}", path.display()))?;
+            if !path.is_dir() {
+                return Err(format!("cannot pin '{}': not a directory", path.display()).into());
+            }
+            pins.insert(path);
+        }
+        pins.save()?;
+        return Ok(ExitCode::SUCCESS);
+    }
+
+    if let Some(Command::History(history)) = &cli.command {
+        match history.command {
+            HistoryCommand::Clear(_) => History::clear()?,
+        }
+        return Ok(ExitCode::SUCCESS);
+    }
+
+    if let Some(Command::Keys(_)) = cli.command {
+        for (key, command) in keymap.bindings() {
+            println!("{:<16} {command}", key.to_string());
+        }
+        return Ok(ExitCode::SUCCESS);
+    }
+
+    let theme = match cli.theme.as_ref().or(config.theme.as_ref()) {
+        Some(name) => Theme::load(name)?,
+        None => Theme::default(),
+    };
+    init_theme(theme.adapt(ColorSupport::detect(cli.color)));
+    let mut output = cli.output()?;
 
     let searchpath = env::var("HOME")?;
 
@@ -22,25 +164,37 @@ fn main() -> AppResult<()> {
         return Err(format!("'{}' is not a directory", searchpath).into());
     }
 
-    let start = Instant::now();
     // Create an application.
-    let mut app = App::new(
-        GlobWalkerBuilder::from_patterns(
-            searchpath,
-            &[
-                "{.git,Cargo.toml,package.json,Makefile,go.mod,composer.json,__manifest__.py}",
-                "!{node_modules,target,build,dist,cmake*,.*}",
-            ],
-        )
-        .build()?
-        .filter_map(|d| Some(Project::new(d.ok()?.into_path()))),
-    );
-    app.start_time = start.elapsed();
+    let mut app = App::default();
+    app.actions = config.actions();
+    app.keymap = keymap;
+    app.set_vim(config.vim);
+    app.layout = config.layout;
+    app.set_sort_order(cli.sort.or(config.sort).unwrap_or_default());
+    app.set_nested(config.nested);
+    app.pins = Pins::load()?;
+    if config.history {
+        app.history = Some(History::load()?);
+    }
 
     // Initialize the terminal user interface.
-    let terminal = Terminal::new(CrosstermBackend::new(io::stderr()))?;
-    let events = EventHandler::new(250);
+    let viewport = match cli.inline_height() {
+        Some(height) => Viewport::Inline(height.lines(crossterm::terminal::size()?.1)),
+        None => Viewport::Fullscreen,
+    };
+    let inline = matches!(viewport, Viewport::Inline(_));
+    let terminal = Terminal::with_options(
+        CrosstermBackend::new(io::stderr()),
+        TerminalOptions { viewport },
+    )?;
+    let events = EventHandler::new(100);
+
+    app.start_scan();
+    scanner::spawn(Path::new(&searchpath), events.sender())?;
+
     let mut tui = Tui::new(terminal, events);
+    tui.mouse_capture = !cli.no_mouse;
+    tui.inline = inline;
     tui.init()?;
 
     // Start the main loop.
@@ -51,11 +205,51 @@ fn main() -> AppResult<()> {
         match tui.events.next()? {
             Event::Tick => app.tick(),
             Event::Key(key_event) => handle_key_events(key_event, &mut app)?,
+            Event::Mouse(mouse_event) => handle_mouse_events(mouse_event, &mut app)?,
+            Event::Paste(text) => app.paste(&text),
+            Event::Projects(projects) => app.add_projects(projects),
+            Event::ScanFinished => app.finish_scan(),
             _ => {}
         }
     }
 
     // Exit the user interface.
     tui.exit()?;
-    Ok(())
+
+    if let Some(name) = app.previewed_theme() {
+        eprintln!("Theme: {name}, set `theme = \"{name}\"` in the configuration to keep it");
+    }
+
+    match &app.state {
+        AppState::Selected(projects) => {
+            for (i, project) in projects.iter().enumerate() {
+                if i > 0 {
+                    writeln!(output)?;
+                }
+                write!(output, "{}", project.path.display())?;
+            }
+            output.flush()?;
+        }
+        AppState::Run(action, project) => action.run(project, &mut output)?,
+        _ => {}
+    }
+
+    if let Err(err) = app.pins.save() {
+        eprintln!("Warning: {err}");
+    }
+
+    if let Some(history) = &mut app.history {
+        match &app.state {
+            AppState::Selected(projects) => projects
+                .iter()
+                .for_each(|project| history.record(&project.path)),
+            AppState::Run(_, project) => history.record(&project.path),
+            _ => {}
+        }
+        if let Err(err) = history.save() {
+            eprintln!("Warning: {err}");
+        }
+    }
+
+    Ok(app.state.exit_code())
 }
@@ -0,0 +1,124 @@
+use std::{
+    collections::BTreeSet,
+    fs, io,
+    path::{Path, PathBuf},
+};
+
+use crate::{app::AppResult, config::Config};
+
+/// Projects pinned by the user, always listed first.
+///
+/// Pins are stored in `$XDG_STATE_HOME/fp/pins`, one path per line.
+#[derive(Debug, Default)]
+pub struct Pins {
+    paths: BTreeSet<PathBuf>,
+    /// Whether the pins changed since they were loaded.
+    changed: bool,
+}
+
+impl Pins {
+    /// Returns the path of the file holding the pins.
+    pub fn file() -> Option<PathBuf> {
+        Config::state_dir().map(|dir| dir.join("pins"))
+    }
+
+    /// Loads the pins from the state file, a missing file meaning no pins.
+    pub fn load() -> AppResult<Self> {
+        let Some(path) = Self::file() else {
+            return Ok(Self::default());
+        };
+
+        match fs::read_to_string(&path) {
+            Ok(content) => Ok(Self::parse(&content)),
+            Err(err) if err.kind() == io::ErrorKind::NotFound => Ok(Self::default()),
+            Err(err) => Err(format!("cannot read '{}': {err}", path.display()).into()),
+        }
+    }
+
+    fn parse(content: &str) -> Self {
+        Self {
+            paths: content
+                .lines()
+                .filter(|line| !line.trim().is_empty())
+                .map(PathBuf::from)
+                .collect(),
+            changed: false,
+        }
+    }
+
+    /// Writes the pins to the state file if they changed.
+    pub fn save(&mut self) -> AppResult<()> {
+        if !self.changed {
+            return Ok(());
+        }
+
+        let path = Self::file().ok_or("cannot locate the state directory, HOME is not set")?;
+        if let Some(dir) = path.parent() {
+            fs::create_dir_all(dir)
+                .map_err(|err| format!("cannot create '{}': {err}", dir.display()))?;
+        }
+
+        let content: String = self
+            .paths
+            .iter()
+            .map(|path| format!("{}\n", path.display()))
+            .collect();
+        fs::write(&path, content)
+            .map_err(|err| format!("cannot write '{}': {err}", path.display()))?;
+
+        self.changed = false;
+        Ok(())
+    }
+
+    pub fn contains(&self, path: &Path) -> bool {
+        self.paths.contains(path)
+    }
+
+    /// Returns the pinned paths, sorted.
+    pub fn iter(&self) -> impl Iterator<Item = &Path> {
+        self.paths.iter().map(PathBuf::as_path)
+    }
+
+    pub fn insert(&mut self, path: PathBuf) {
+        self.changed |= self.paths.insert(path);
+    }
+
+    pub fn remove(&mut self, path: &Path) {
+        self.changed |= self.paths.remove(path);
+    }
+
+    /// Pins the path if it is not pinned, unpins it otherwise.
+    pub fn toggle(&mut self, path: &Path) {
+        if self.contains(path) {
+            self.remove(path);
+        } else {
+            self.insert(path.to_path_buf());
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse() {
+        let pins = Pins::parse("/home/alpha\n\n/home/beta\n");
+
+        assert!(pins.contains(Path::new("/home/alpha")));
+        assert!(pins.contains(Path::new("/home/beta")));
+        assert_eq!(pins.iter().count(), 2);
+    }
+
+    #[test]
+    fn toggle() {
+        let mut pins = Pins::default();
+
+        pins.toggle(Path::new("/home/alpha"));
+        assert!(pins.contains(Path::new("/home/alpha")));
+        assert!(pins.changed);
+
+        pins.toggle(Path::new("/home/alpha"));
+        assert!(!pins.contains(Path::new("/home/alpha")));
+    }
+}
@@ -1,4 +1,4 @@
-use std::{cmp::Ordering, fmt::Display, fs::DirEntry, path::PathBuf};
+use std::{cmp::Ordering, fmt::Display, fs::DirEntry, path::PathBuf, time::SystemTime};
 
 use ratatui::{
     prelude::{style::Styled, Line},
@@ -7,15 +7,56 @@ use ratatui::{
 
 use crate::theme::theme;
 
+mod kind;
+mod order;
+mod workspace;
+
+pub use kind::*;
+pub use order::SortOrder;
+
 #[derive(Clone, Debug, PartialEq, Eq, PartialOrd, Ord)]
 pub struct Project {
     pub path: PathBuf,
+    pub kind: ProjectKind,
+    /// Root of the workspace this project is a member of, if any.
+    pub workspace: Option<PathBuf>,
+    /// When the project was last modified, as of its discovery.
+    pub modified: Option<SystemTime>,
 }
 
 impl Project {
+    /// Creates a project from the path of the file marking its directory as a project.
     pub fn new(mut path: PathBuf) -> Self {
+        let kind = path
+            .file_name()
+            .map(ProjectKind::from_marker)
+            .unwrap_or_default();
         path.pop();
-        Self { path }
+        Self {
+            modified: order::modified(&path),
+            path,
+            kind,
+            workspace: None,
+        }
+    }
+
+    /// Creates a git project at the given path, without reading anything from the disk.
+    #[cfg(test)]
+    pub fn test(path: impl Into<PathBuf>) -> Self {
+        Self {
+            path: path.into(),
+            kind: ProjectKind::Git,
+            workspace: None,
+            modified: None,
+        }
+    }
+
+    /// Returns the name of the project, that is the name of its directory.
+    pub fn name(&self) -> String {
+        self.path
+            .file_name()
+            .map(|name| name.to_string_lossy().into_owned())
+            .unwrap_or_default()
     }
 
     pub fn files(&self) -> Vec<DirEntry> {
@@ -50,9 +91,9 @@ impl Project {
     }
 }
 
-impl From<Project> for ListItem<'_> {
-    fn from(value: Project) -> Self {
-        Self::from(Line::from(vec![
+impl From<&Project> for Line<'_> {
+    fn from(value: &Project) -> Self {
+        Self::from(vec![
             value
                 .path
                 .file_name()
@@ -60,7 +101,7 @@ impl From<Project> for ListItem<'_> {
                 .to_str()
                 .unwrap()
                 .to_string()
-                .into(),
+                .set_style(theme().project_name),
             " ".into(),
             value
                 .path
@@ -68,7 +109,13 @@ impl From<Project> for ListItem<'_> {
                 .unwrap()
                 .to_string()
                 .set_style(theme().project_path),
-        ]))
+        ])
+    }
+}
+
+impl From<Project> for ListItem<'_> {
+    fn from(value: Project) -> Self {
+        Self::from(Line::from(&value))
     }
 }
 
@@ -0,0 +1,47 @@
+use std::{ffi::OsStr, fmt::Display};
+
+/// The kind of a project, deduced from the file that marked its directory as a project.
+///
+/// Variants are ordered by priority, when a directory has multiple markers the first kind wins.
+#[derive(Default, Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum ProjectKind {
+    Rust,
+    Node,
+    Go,
+    Php,
+    Odoo,
+    Make,
+    #[default]
+    Git,
+}
+
+impl ProjectKind {
+    /// Returns the kind of project marked by the given file name.
+    pub fn from_marker(marker: &OsStr) -> Self {
+        match marker.to_str().unwrap_or_default() {
+            "Cargo.toml" => Self::Rust,
+            "package.json" | "pnpm-workspace.yaml" => Self::Node,
+            "go.mod" | "go.work" => Self::Go,
+            "composer.json" => Self::Php,
+            "__manifest__.py" => Self::Odoo,
+            "Makefile" => Self::Make,
+            _ => Self::Git,
+        }
+    }
+}
+
+impl Display for ProjectKind {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let name = match self {
+            Self::Rust => "rust",
+            Self::Node => "node",
+            Self::Go => "go",
+            Self::Php => "php",
+            Self::Odoo => "odoo",
+            Self::Make => "make",
+            Self::Git => "git",
+        };
+
+        f.write_str(name)
+    }
+}
@@ -0,0 +1,143 @@
+use std::{
+    cmp::{Ordering, Reverse},
+    fmt::Display,
+    fs,
+    path::Path,
+    str::FromStr,
+    time::SystemTime,
+};
+
+use serde::Deserialize;
+
+use super::Project;
+
+/// Order of the project list when nothing is searched.
+#[derive(Default, Clone, Copy, Debug, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum SortOrder {
+    /// Alphabetical by path.
+    #[default]
+    Path,
+    /// Alphabetical by name, case insensitive.
+    Name,
+    /// Most recently modified first, according to the directory or its git reflog.
+    Modified,
+    /// Least nested first.
+    Depth,
+}
+
+impl SortOrder {
+    /// Every order, in the order they are cycled through.
+    pub const ALL: [Self; 4] = [Self::Path, Self::Name, Self::Modified, Self::Depth];
+
+    /// Returns the order following this one, cycling back to the first one.
+    pub fn next(self) -> Self {
+        let index = Self::ALL.iter().position(|order| *order == self);
+        Self::ALL[index.map_or(0, |index| (index + 1) % Self::ALL.len())]
+    }
+
+    /// Sorts the projects, ties being ordered by path.
+    ///
+    /// The projects are expected to be sorted by path already.
+    pub fn sort(self, projects: &mut [Project]) {
+        match self {
+            Self::Path => {}
+            Self::Name => projects.sort_by_cached_key(|project| project.name().to_lowercase()),
+            Self::Modified => projects.sort_by_key(|project| Reverse(project.modified)),
+            Self::Depth => projects.sort_by_key(depth),
+        }
+    }
+
+    /// Compares two projects according to the order, ties being ordered by path.
+    pub fn compare(self, a: &Project, b: &Project) -> Ordering {
+        let order = match self {
+            Self::Path => Ordering::Equal,
+            Self::Name => a.name().to_lowercase().cmp(&b.name().to_lowercase()),
+            Self::Modified => b.modified.cmp(&a.modified),
+            Self::Depth => depth(a).cmp(&depth(b)),
+        };
+        order.then_with(|| a.path.cmp(&b.path))
+    }
+}
+
+/// Returns the number of components of the project path.
+fn depth(project: &Project) -> usize {
+    project.path.components().count()
+}
+
+/// Returns when the project at the given path was last modified, either its directory or its git
+/// history.
+///
+/// The git reflog is updated by commits, but also by checkouts, resets and rebases.
+pub(super) fn modified(path: &Path) -> Option<SystemTime> {
+    let mtime = |path: &Path| {
+        fs::metadata(path)
+            .and_then(|metadata| metadata.modified())
+            .ok()
+    };
+
+    mtime(path).max(mtime(&path.join(".git/logs/HEAD")))
+}
+
+impl FromStr for SortOrder {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Self::ALL
+            .into_iter()
+            .find(|order| order.to_string() == s)
+            .ok_or_else(|| {
+                format!("unknown sort order '{s}', expected path, name, modified or depth")
+            })
+    }
+}
+
+impl Display for SortOrder {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let name = match self {
+            Self::Path => "path",
+            Self::Name => "name",
+            Self::Modified => "modified",
+            Self::Depth => "depth",
+        };
+
+        f.write_str(name)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn projects() -> Vec<Project> {
+        ["/home/b/Alpha", "/home/beta", "/home/c/d/gamma"]
+            .map(Project::test)
+            .to_vec()
+    }
+
+    fn names(projects: &[Project]) -> Vec<String> {
+        projects.iter().map(Project::name).collect()
+    }
+
+    #[test]
+    fn sort() {
+        let mut projects = projects();
+
+        SortOrder::Name.sort(&mut projects);
+        assert_eq!(names(&projects), ["Alpha", "beta", "gamma"]);
+
+        SortOrder::Depth.sort(&mut projects);
+        assert_eq!(names(&projects), ["beta", "Alpha", "gamma"]);
+    }
+
+    #[test]
+    fn cycle() {
+        let mut order = SortOrder::default();
+
+        for _ in SortOrder::ALL {
+            order = order.next();
+            assert_eq!(order.to_string().parse(), Ok(order));
+        }
+        assert_eq!(order, SortOrder::default());
+    }
+}
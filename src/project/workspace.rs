@@ -0,0 +1,343 @@
+use std::{
+    fs,
+    path::{Path, PathBuf},
+};
+
+use globwalk::{FileType, GlobWalkerBuilder};
+use serde::Deserialize;
+
+use super::{Project, ProjectKind};
+use crate::scanner::IGNORED;
+
+impl Project {
+    /// Returns the members of the workspace defined by the given manifest, if it defines one.
+    ///
+    /// Workspaces are defined by the `[workspace]` section of `Cargo.toml`, the `workspaces` of
+    /// `package.json`, `pnpm-workspace.yaml` and `go.work`. Members are returned as projects of
+    /// the workspace kind, whose [`Project::workspace`] is the directory of the manifest.
+    pub fn workspace_members(manifest: &Path) -> Vec<Self> {
+        let name = manifest.file_name().and_then(|name| name.to_str());
+        if !matches!(
+            name,
+            Some("Cargo.toml" | "package.json" | "pnpm-workspace.yaml" | "go.work")
+        ) {
+            return Vec::new();
+        }
+
+        let Some(root) = manifest.parent() else {
+            return Vec::new();
+        };
+        let Ok(content) = fs::read_to_string(manifest) else {
+            return Vec::new();
+        };
+
+        let (kind, members) = match name {
+            Some("Cargo.toml") => (ProjectKind::Rust, expand(root, &cargo_members(&content))),
+            Some("package.json") => (ProjectKind::Node, expand(root, &npm_members(&content))),
+            Some("pnpm-workspace.yaml") => {
+                (ProjectKind::Node, expand(root, &pnpm_members(&content)))
+            }
+            Some("go.work") => (ProjectKind::Go, go_members(root, &content)),
+            _ => return Vec::new(),
+        };
+
+        members
+            .into_iter()
+            .filter(|path| path != root)
+            .map(|path| Self {
+                modified: super::order::modified(&path),
+                path,
+                kind,
+                workspace: Some(root.to_path_buf()),
+            })
+            .collect()
+    }
+}
+
+/// Returns the directories matching the member patterns, relative to `root`.
+///
+/// Patterns starting with `!` exclude the directories they match. Patterns are anchored to `root`,
+/// so that `foo` does not match `vendor/foo`.
+fn expand(root: &Path, patterns: &[String]) -> Vec<PathBuf> {
+    let mut patterns: Vec<_> = patterns
+        .iter()
+        .filter_map(|pattern| {
+            let (negation, pattern) = match pattern.strip_prefix('!') {
+                Some(pattern) => ("!", pattern),
+                None => ("", pattern.as_str()),
+            };
+            let pattern = pattern
+                .trim_start_matches("./")
+                .trim_start_matches('/')
+                .trim_end_matches('/');
+            (!pattern.is_empty() && pattern != ".").then(|| format!("{negation}/{pattern}"))
+        })
+        .collect();
+
+    let included = patterns.iter().filter(|pattern| !pattern.starts_with('!'));
+    if included.clone().next().is_none() {
+        return Vec::new();
+    }
+
+    // Members are usually one or two levels deep, only `**` requires a full search
+    let depth = included
+        .map(|pattern| {
+            if pattern.contains("**") {
+                usize::MAX
+            } else {
+                pattern.split('/').count() - 1
+            }
+        })
+        .max()
+        .unwrap_or_default();
+
+    patterns.push(IGNORED.to_string());
+    let walker = GlobWalkerBuilder::from_patterns(root, &patterns)
+        .max_depth(depth)
+        .file_type(FileType::DIR)
+        .build();
+
+    match walker {
+        Ok(walker) => walker
+            .filter_map(Result::ok)
+            .map(|entry| entry.into_path())
+            .collect(),
+        Err(_) => Vec::new(),
+    }
+}
+
+/// Returns the member patterns of a Cargo workspace, the excluded ones being negated.
+fn cargo_members(content: &str) -> Vec<String> {
+    #[derive(Deserialize)]
+    struct Manifest {
+        workspace: Option<Workspace>,
+    }
+
+    #[derive(Deserialize)]
+    struct Workspace {
+        #[serde(default)]
+        members: Vec<String>,
+        #[serde(default)]
+        exclude: Vec<String>,
+    }
+
+    // Avoid parsing the manifests of every crate
+    if !content.contains("[workspace") {
+        return Vec::new();
+    }
+
+    let Some(workspace) = toml::from_str::<Manifest>(content)
+        .ok()
+        .and_then(|manifest| manifest.workspace)
+    else {
+        return Vec::new();
+    };
+
+    let excluded = workspace
+        .exclude
+        .iter()
+        .map(|pattern| format!("!{pattern}"));
+    workspace.members.iter().cloned().chain(excluded).collect()
+}
+
+/// Returns the member patterns of an npm or yarn workspace.
+///
+/// `workspaces` is either a list of patterns or an object with a `packages` list.
+fn npm_members(content: &str) -> Vec<String> {
+    #[derive(Deserialize)]
+    struct Manifest {
+        workspaces: Option<Workspaces>,
+    }
+
+    #[derive(Deserialize)]
+    #[serde(untagged)]
+    enum Workspaces {
+        List(Vec<String>),
+        Object {
+            #[serde(default)]
+            packages: Vec<String>,
+        },
+    }
+
+    // Avoid parsing the manifests of every package
+    if !content.contains("\"workspaces\"") {
+        return Vec::new();
+    }
+
+    match serde_json::from_str::<Manifest>(content)
+        .ok()
+        .and_then(|manifest| manifest.workspaces)
+    {
+        Some(Workspaces::List(patterns) | Workspaces::Object { packages: patterns }) => patterns,
+        None => Vec::new(),
+    }
+}
+
+/// Returns the member patterns of a pnpm workspace, listed under `packages`.
+///
+/// Only the block and flow sequences used in practice are supported, not the whole YAML syntax.
+fn pnpm_members(content: &str) -> Vec<String> {
+    let unquote = |value: &str| {
+        let value = value.trim();
+        value
+            .strip_prefix('\'')
+            .and_then(|value| value.strip_suffix('\''))
+            .or_else(|| {
+                value
+                    .strip_prefix('"')
+                    .and_then(|value| value.strip_suffix('"'))
+            })
+            .unwrap_or(value)
+            .to_string()
+    };
+
+    let mut lines = content
+        .lines()
+        .skip_while(|line| !line.starts_with("packages:"));
+    let Some(packages) = lines.next() else {
+        return Vec::new();
+    };
+
+    // packages: ['packages/*', 'apps/*']
+    if let Some(flow) = packages["packages:".len()..]
+        .trim()
+        .strip_prefix('[')
+        .and_then(|flow| flow.strip_suffix(']'))
+    {
+        return flow
+            .split(',')
+            .map(unquote)
+            .filter(|p| !p.is_empty())
+            .collect();
+    }
+
+    lines
+        .map(|line| line.split(" #").next().unwrap_or_default())
+        .filter(|line| !line.trim().is_empty() && !line.trim_start().starts_with('#'))
+        .take_while(|line| line.starts_with([' ', '\t', '-']))
+        .filter_map(|line| line.trim_start().strip_prefix('-'))
+        .map(unquote)
+        .collect()
+}
+
+/// Returns the directories of the modules used by a Go workspace.
+fn go_members(root: &Path, content: &str) -> Vec<PathBuf> {
+    let mut members = Vec::new();
+    let mut in_block = false;
+
+    for line in content.lines() {
+        let line = line.split("//").next().unwrap_or_default().trim();
+
+        let path = if in_block {
+            in_block = line != ")";
+            line
+        } else if let Some(rest) = line.strip_prefix("use") {
+            let rest = rest.trim();
+            in_block = rest == "(";
+            rest
+        } else {
+            continue;
+        };
+
+        let path = path.trim_matches(['"', '`']).trim_start_matches("./");
+        if !path.is_empty() && path != "(" && path != ")" {
+            let path = root.join(path);
+            if path.is_dir() {
+                members.push(path);
+            }
+        }
+    }
+
+    members
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn cargo() {
+        let content = r#"
+            [workspace]
+            members = ["crates/*", "xtask"]
+            exclude = ["crates/old"]
+        "#;
+
+        assert_eq!(cargo_members(content), ["crates/*", "xtask", "!crates/old"]);
+        assert!(cargo_members("[package]\nname = \"fp\"").is_empty());
+    }
+
+    #[test]
+    fn npm() {
+        let content =
+            r#"{ "name": "mono", "private": true, "workspaces": ["packages/*", "apps/web"] }"#;
+        assert_eq!(npm_members(content), ["packages/*", "apps/web"]);
+
+        let content = r#"{ "workspaces": { "packages": ["packages/*"], "nohoist": ["**/x"] } }"#;
+        assert_eq!(npm_members(content), ["packages/*"]);
+
+        assert!(npm_members(r#"{ "workspaces": "#).is_empty());
+    }
+
+    #[test]
+    fn pnpm() {
+        let content = r#"
+packages:
+  # apps
+  - 'apps/*'
+  - "packages/**" # all
+  - '!**/test/**'
+catalog:
+  - x
+"#;
+        assert_eq!(
+            pnpm_members(content),
+            ["apps/*", "packages/**", "!**/test/**"]
+        );
+
+        assert_eq!(pnpm_members("packages: ['a/*', b]\n"), ["a/*", "b"]);
+    }
+
+    #[test]
+    fn members() {
+        let root = std::env::temp_dir().join(format!("fp-workspace-{}", std::process::id()));
+        for dir in [
+            "crates/a",
+            "crates/b",
+            "crates/old",
+            "xtask",
+            "other",
+            "mod",
+            "vendor/xtask",
+        ] {
+            fs::create_dir_all(root.join(dir)).unwrap();
+        }
+        fs::write(
+            root.join("Cargo.toml"),
+            "[workspace]\nmembers = [\"crates/*\", \"xtask\"]\nexclude = [\"crates/old\"]\n",
+        )
+        .unwrap();
+        fs::write(
+            root.join("go.work"),
+            "go 1.22\n\nuse (\n\t./mod // module\n\t.\n)\nuse ./missing\n",
+        )
+        .unwrap();
+
+        let names = |manifest: &str| {
+            let mut names: Vec<_> = Project::workspace_members(&root.join(manifest))
+                .iter()
+                .map(|project| project.path.strip_prefix(&root).unwrap().to_path_buf())
+                .collect();
+            names.sort();
+            names
+        };
+
+        assert_eq!(
+            names("Cargo.toml"),
+            ["crates/a", "crates/b", "xtask"].map(PathBuf::from)
+        );
+        assert_eq!(names("go.work"), [PathBuf::from("mod")]);
+
+        fs::remove_dir_all(root).unwrap();
+    }
+}
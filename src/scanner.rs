@@ -0,0 +1,119 @@
+use std::{
+    mem,
+    path::{Path, PathBuf},
+    sync::mpsc,
+    thread,
+    time::{Duration, Instant},
+};
+
+use globwalk::GlobWalkerBuilder;
+
+use crate::{app::AppResult, event::Event, project::Project};
+
+/// Directories that are never searched.
+pub const IGNORED: &str = "!{node_modules,target,build,dist,cmake*,.*}";
+
+/// Files marking a directory as a project, and directories that are never searched.
+const PATTERNS: [&str; 2] = [
+    concat!(
+        "{.git,Cargo.toml,package.json,pnpm-workspace.yaml,Makefile,go.mod,go.work,",
+        "composer.json,__manifest__.py}"
+    ),
+    IGNORED,
+];
+
+/// Minimum delay between two batches of projects, so that the list is not filtered for every
+/// project found.
+const BATCH_INTERVAL: Duration = Duration::from_millis(50);
+
+/// Searches for projects in `root` in a background thread.
+///
+/// Projects are sent in batches as [`Event::Projects`], followed by [`Event::ScanFinished`]. The
+/// search stops early once the receiver is dropped.
+///
+/// The canonical path of `root` is searched, so that the projects match the pinned paths, which
+/// are canonicalized too.
+pub fn spawn(root: &Path, sender: mpsc::Sender<Event>) -> AppResult<()> {
+    let root = root
+        .canonicalize()
+        .map_err(|err| format!("cannot search '{}': {err}", root.display()))?;
+
+    // Files come first so that the manifest of a workspace is found before its members
+    let walker = GlobWalkerBuilder::from_patterns(&root, &PATTERNS)
+        .sort_by(|a, b| a.file_type().is_dir().cmp(&b.file_type().is_dir()))
+        .build()?;
+
+    thread::spawn(move || {
+        let mut batch = Vec::new();
+        let mut last_batch = Instant::now();
+        let mut workspaces: Vec<PathBuf> = Vec::new();
+
+        for entry in walker.filter_map(Result::ok) {
+            let marker = entry.into_path();
+            let project = Project::new(marker.clone());
+
+            // Reading a manifest and searching for its members is only done for the projects that
+            // are not already members of a workspace, nested workspaces being rare
+            let nested = workspaces
+                .iter()
+                .any(|root| project.path.starts_with(root) && project.path != *root);
+            if !nested {
+                let members = Project::workspace_members(&marker);
+                if !members.is_empty() {
+                    workspaces.push(project.path.clone());
+                }
+                batch.extend(members);
+            }
+            batch.push(project);
+
+            if last_batch.elapsed() >= BATCH_INTERVAL {
+                if sender.send(Event::Projects(mem::take(&mut batch))).is_err() {
+                    return;
+                }
+                last_batch = Instant::now();
+            }
+        }
+
+        let _ = sender.send(Event::Projects(batch));
+        let _ = sender.send(Event::ScanFinished);
+    });
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use std::fs;
+
+    use super::*;
+    use crate::pins::Pins;
+
+    #[cfg(unix)]
+    #[test]
+    fn symlinked_root() {
+        let root = std::env::temp_dir().join(format!("fp-scanner-{}", std::process::id()));
+        fs::create_dir_all(root.join("real/alpha")).unwrap();
+        fs::write(root.join("real/alpha/Cargo.toml"), "").unwrap();
+        let link = root.join("link");
+        if !link.exists() {
+            std::os::unix::fs::symlink(root.join("real"), &link).unwrap();
+        }
+
+        let (sender, receiver) = mpsc::channel();
+        spawn(&link, sender).unwrap();
+        let mut projects = Vec::new();
+        for event in receiver {
+            if let Event::Projects(batch) = event {
+                projects.extend(batch);
+            }
+        }
+
+        let mut pins = Pins::default();
+        pins.insert(link.join("alpha").canonicalize().unwrap());
+
+        assert_eq!(projects.len(), 1);
+        assert!(pins.contains(&projects[0].path));
+
+        fs::remove_dir_all(root).unwrap();
+    }
+}
@@ -0,0 +1,160 @@
+use std::str::FromStr;
+
+/// A shell `fp` can generate an integration script for.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Shell {
+    Bash,
+    Zsh,
+    Fish,
+    Nushell,
+    Powershell,
+}
+
+impl Shell {
+    /// Returns the script defining the `fp` wrapper function.
+    ///
+    /// The wrapper reads the selection from a temporary file given to `--output-file`, and only
+    /// changes directory when a project has actually been selected.
+    fn wrapper(self) -> &'static str {
+        match self {
+            Self::Bash => include_str!("shell/fp.bash"),
+            Self::Zsh => include_str!("shell/fp.zsh"),
+            Self::Fish => include_str!("shell/fp.fish"),
+            Self::Nushell => include_str!("shell/fp.nu"),
+            Self::Powershell => include_str!("shell/fp.ps1"),
+        }
+    }
+
+    /// Returns the script binding `Ctrl-G` to the wrapper function.
+    fn key_binding(self) -> &'static str {
+        match self {
+            Self::Bash => include_str!("shell/bind.bash"),
+            Self::Zsh => include_str!("shell/bind.zsh"),
+            Self::Fish => include_str!("shell/bind.fish"),
+            Self::Nushell => include_str!("shell/bind.nu"),
+            Self::Powershell => include_str!("shell/bind.ps1"),
+        }
+    }
+
+    /// Generates the integration script for this shell.
+    pub fn init_script(self, key_binding: bool) -> String {
+        let mut script = self.wrapper().to_string();
+
+        if key_binding {
+            script.push_str(self.key_binding());
+        }
+
+        script
+    }
+}
+
+impl FromStr for Shell {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().trim() {
+            "bash" => Ok(Self::Bash),
+            "zsh" => Ok(Self::Zsh),
+            "fish" => Ok(Self::Fish),
+            "nu" | "nushell" => Ok(Self::Nushell),
+            "pwsh" | "powershell" => Ok(Self::Powershell),
+            s => Err(format!(
+                "unknown shell '{s}', expected one of: bash, zsh, fish, nushell, powershell"
+            )),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SHELLS: [Shell; 5] = [
+        Shell::Bash,
+        Shell::Zsh,
+        Shell::Fish,
+        Shell::Nushell,
+        Shell::Powershell,
+    ];
+
+    #[test]
+    fn from_str() {
+        assert_eq!(Shell::from_str("bash").unwrap(), Shell::Bash);
+        assert_eq!(Shell::from_str(" ZSH ").unwrap(), Shell::Zsh);
+        assert_eq!(Shell::from_str("fish").unwrap(), Shell::Fish);
+        assert_eq!(Shell::from_str("nu").unwrap(), Shell::Nushell);
+        assert_eq!(Shell::from_str("nushell").unwrap(), Shell::Nushell);
+        assert_eq!(Shell::from_str("pwsh").unwrap(), Shell::Powershell);
+        assert_eq!(Shell::from_str("PowerShell").unwrap(), Shell::Powershell);
+        assert!(Shell::from_str("cmd").is_err());
+    }
+
+    #[test]
+    fn posix_wrapper() {
+        for shell in [Shell::Bash, Shell::Zsh] {
+            let script = shell.init_script(false);
+
+            assert!(script.contains("fp() {"));
+            assert!(script.contains(r#"command fp --output-file "$tmp" "$@""#));
+            assert!(script.contains(r#"IFS= read -r -d '' dir <"$tmp""#));
+            assert!(script.contains(r#"rm -f -- "$tmp""#));
+            assert!(script.contains(r#"builtin cd -- "$dir""#));
+            assert!(script.contains(r#"printf '%s\n' "$dir""#));
+        }
+    }
+
+    #[test]
+    fn fish_wrapper() {
+        let script = Shell::Fish.init_script(false);
+
+        assert!(script.contains("function fp"));
+        assert!(script.contains("command fp --output-file $tmp $argv"));
+        assert!(script.contains("read -lz dir <$tmp"));
+        assert!(script.contains("builtin cd -- $dir"));
+    }
+
+    #[test]
+    fn nushell_wrapper() {
+        let script = Shell::Nushell.init_script(false);
+
+        assert!(script.contains("def --env --wrapped fp [...rest: string]"));
+        assert!(script.contains("^fp --output-file $tmp ...$rest"));
+        assert!(script.contains("let code = $env.LAST_EXIT_CODE"));
+        assert!(script.contains("cd $dir"));
+    }
+
+    #[test]
+    fn powershell_wrapper() {
+        let script = Shell::Powershell.init_script(false);
+
+        assert!(script.contains("function fp {"));
+        assert!(script.contains("-CommandType Application"));
+        assert!(script.contains("& $fp --output-file $tmp.FullName @args"));
+        assert!(script.contains("Set-Location -LiteralPath $dir"));
+    }
+
+    #[test]
+    fn key_binding() {
+        assert!(Shell::Bash
+            .init_script(true)
+            .contains("bind -x '\"\\C-g\": fp'"));
+        assert!(Shell::Zsh
+            .init_script(true)
+            .contains("bindkey '^G' __fp_widget"));
+        assert!(Shell::Fish
+            .init_script(true)
+            .contains("bind \\cg 'fp; commandline -f repaint'"));
+        assert!(Shell::Nushell.init_script(true).contains("keycode: char_g"));
+        assert!(Shell::Powershell
+            .init_script(true)
+            .contains("Set-PSReadLineKeyHandler -Chord Ctrl+g"));
+    }
+
+    #[test]
+    fn no_key_binding() {
+        for shell in SHELLS {
+            assert_eq!(shell.init_script(false), shell.wrapper());
+            assert!(shell.init_script(true).starts_with(shell.wrapper()));
+        }
+    }
+}
@@ -0,0 +1,4 @@
+
+if [[ $- == *i* ]]; then
+    bind -x '"\C-g": fp'
+fi
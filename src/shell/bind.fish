@@ -0,0 +1,2 @@
+
+bind \cg 'fp; commandline -f repaint'
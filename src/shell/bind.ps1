@@ -0,0 +1,5 @@
+
+Set-PSReadLineKeyHandler -Chord Ctrl+g -ScriptBlock {
+    fp
+    [Microsoft.PowerShell.PSConsoleReadLine]::InvokePrompt()
+}
@@ -0,0 +1,20 @@
+# fp shell integration for bash.
+#
+# Add this to your ~/.bashrc:
+#
+#   eval "$(fp init bash)"
+
+fp() {
+    local tmp dir ret
+    tmp="$(mktemp -t fp.XXXXXX)" || return
+    command fp --output-file "$tmp" "$@"
+    ret=$?
+    IFS= read -r -d '' dir <"$tmp"
+    rm -f -- "$tmp"
+    if [ "$ret" -eq 0 ] && [ -d "$dir" ]; then
+        builtin cd -- "$dir" || return
+    elif [ "$ret" -eq 0 ] && [ -n "$dir" ]; then
+        printf '%s\n' "$dir"
+    fi
+    return "$ret"
+}
@@ -0,0 +1,22 @@
+# fp shell integration for fish.
+#
+# Add this to your ~/.config/fish/config.fish:
+#
+#   fp init fish | source
+
+function fp --description 'Find a project and cd into it'
+    set -l tmp (mktemp -t fp.XXXXXX)
+    or return
+
+    command fp --output-file $tmp $argv
+    set -l ret $status
+    read -lz dir <$tmp
+    rm -f -- $tmp
+
+    if test $ret -eq 0; and test -d "$dir"
+        builtin cd -- $dir
+    else if test $ret -eq 0; and test -n "$dir"
+        printf '%s\n' $dir
+    end
+    return $ret
+end
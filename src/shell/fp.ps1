@@ -0,0 +1,23 @@
+# fp shell integration for PowerShell.
+#
+# Add this to your $PROFILE:
+#
+#   Invoke-Expression (& fp init powershell | Out-String)
+
+function fp {
+    $fp = Get-Command -Name fp -CommandType Application | Select-Object -First 1
+    $tmp = New-TemporaryFile
+    try {
+        & $fp --output-file $tmp.FullName @args
+        $code = $LASTEXITCODE
+        $dir = Get-Content -LiteralPath $tmp.FullName -Raw
+
+        if ($code -eq 0 -and $dir -and (Test-Path -LiteralPath $dir -PathType Container)) {
+            Set-Location -LiteralPath $dir
+        } elseif ($code -eq 0 -and $dir) {
+            $dir
+        }
+    } finally {
+        Remove-Item -LiteralPath $tmp.FullName -Force
+    }
+}
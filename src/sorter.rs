@@ -27,15 +27,12 @@ impl Sorter {
             return inner.clone();
         }
 
-        let mut res = vec![String::new(); s.len() - self.ngram_len + 1];
-
-        for (i, item) in res
-            .iter_mut()
-            .enumerate()
-            .take(s.len() + 1 - self.ngram_len)
-        {
-            *item = s.get(i..i + self.ngram_len).unwrap().to_string();
-        }
+        // Slice on character boundaries, the string may contain multibyte characters
+        let bounds: Vec<usize> = s.char_indices().map(|(i, _)| i).chain([s.len()]).collect();
+        let res: Vec<String> = bounds
+            .windows(self.ngram_len + 1)
+            .map(|window| s[window[0]..window[self.ngram_len]].to_string())
+            .collect();
 
         self.cached_ngrams.insert(s.to_string(), res.clone());
 
@@ -72,7 +69,7 @@ impl Sorter {
     pub fn score(&mut self, prompt: &str, line: &str) -> f64 {
         let n = prompt.len();
 
-        if n == 0 || n < self.ngram_len {
+        if n == 0 || prompt.chars().count() < self.ngram_len {
             return 1.;
         }
 
@@ -151,6 +148,22 @@ impl Sorter {
     pub fn sort<I>(&mut self, array: &[I], prompt: &str) -> Vec<I>
     where
         I: ToString + Clone,
+    {
+        self.sort_with(array, prompt, |_, score| score)
+    }
+
+    /// Sorts the given array by `key`, computed from each item and its score, lower being better.
+    ///
+    /// This allows adjusting the scores or ordering the items of equal score.
+    pub fn sort_with<I, K>(
+        &mut self,
+        array: &[I],
+        prompt: &str,
+        key: impl Fn(&I, i16) -> K,
+    ) -> Vec<I>
+    where
+        I: ToString + Clone,
+        K: Ord,
     {
         let mut tmp: Vec<_> = array
             .iter()
@@ -165,7 +178,7 @@ impl Sorter {
             })
             .collect();
 
-        tmp.sort_unstable_by_key(|(_, score)| *score);
+        tmp.sort_unstable_by_key(|(line, score)| key(line, *score));
 
         tmp.into_iter().map(|(item, _)| item.clone()).collect()
     }
@@ -194,7 +207,7 @@ mod tests {
 
             sorter.overlapping_ngrams("fp");
 
-            assert!(sorter.cached_ngrams.get("fp").is_some());
+            assert!(sorter.cached_ngrams.contains_key("fp"));
         }
 
         #[test]
@@ -244,6 +257,23 @@ mod tests {
 
             assert_eq!(res, vec!["mul", "ule"]);
         }
+
+        #[test]
+        fn test_ngram_2_prompt_multibyte() {
+            let mut sorter = Sorter::default();
+
+            let res = sorter.overlapping_ngrams("éaß");
+
+            assert_eq!(res, vec!["éa", "aß"]);
+        }
+
+        #[test]
+        fn test_score_multibyte_prompt() {
+            let mut sorter = Sorter::default();
+
+            assert!(sorter.score("éa", "/home/éa") > 0.);
+            assert_eq!(sorter.score("é", "/home/fp"), 1.);
+        }
     }
 
     mod uppers {
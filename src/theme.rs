@@ -1,22 +1,103 @@
-use std::sync::{LazyLock, OnceLock};
+use std::{
+    fs, io,
+    path::PathBuf,
+    sync::{LazyLock, RwLock},
+};
 
 use lscolors::LsColors;
 use ratatui::prelude::*;
 
+use crate::{app::AppResult, config::Config};
+
+mod background;
+mod colors;
+mod file;
 mod variant;
 
+pub use colors::{ColorMode, ColorSupport};
+pub use file::ThemeFile;
 pub use variant::*;
 
-static THEME: OnceLock<Theme> = OnceLock::new();
+static THEME: RwLock<Option<Theme>> = RwLock::new(None);
+
+/// Themes bundled with `fp`, on top of the `dark` and `light` variants.
+const BUNDLED: [(&str, &str); 11] = [
+    (
+        "catppuccin-frappe",
+        include_str!("theme/themes/catppuccin-frappe.toml"),
+    ),
+    (
+        "catppuccin-latte",
+        include_str!("theme/themes/catppuccin-latte.toml"),
+    ),
+    (
+        "catppuccin-macchiato",
+        include_str!("theme/themes/catppuccin-macchiato.toml"),
+    ),
+    (
+        "catppuccin-mocha",
+        include_str!("theme/themes/catppuccin-mocha.toml"),
+    ),
+    ("dracula", include_str!("theme/themes/dracula.toml")),
+    (
+        "gruvbox-dark",
+        include_str!("theme/themes/gruvbox-dark.toml"),
+    ),
+    (
+        "gruvbox-light",
+        include_str!("theme/themes/gruvbox-light.toml"),
+    ),
+    ("nord", include_str!("theme/themes/nord.toml")),
+    (
+        "solarized-dark",
+        include_str!("theme/themes/solarized-dark.toml"),
+    ),
+    (
+        "solarized-light",
+        include_str!("theme/themes/solarized-light.toml"),
+    ),
+    ("tokyo-night", include_str!("theme/themes/tokyo-night.toml")),
+];
 
 pub static LS_COLORS: LazyLock<LsColors> = LazyLock::new(|| LsColors::from_env().unwrap_or_default());
 
-#[derive(Clone, Copy)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
 pub struct Theme {
     pub variant: ThemeVariant,
     pub border: Style,
+    /// Titles of the borders.
+    pub title: Style,
     pub selected: Style,
+    /// Marker of the marked projects.
+    pub marker: Style,
+    pub project_name: Style,
     pub project_path: Style,
+    /// Characters of the project matching the search.
+    pub matched: Style,
+    /// Icons next to the projects, like the pin.
+    pub icon: Style,
+    /// Title of the preview.
+    pub preview_title: Style,
+    /// Hints and secondary text, like action descriptions.
+    pub hint: Style,
+    /// Colors supported by the terminal, see [`Theme::adapt`].
+    pub colors: ColorSupport,
+}
+
+impl Default for Theme {
+    fn default() -> Self {
+        Self::enfocado_dark()
+    }
+}
+
+impl From<ThemeVariant> for Theme {
+    fn from(variant: ThemeVariant) -> Self {
+        match variant {
+            ThemeVariant::Dark => Self::enfocado_dark(),
+            ThemeVariant::Light => Self::enfocado_light(),
+            ThemeVariant::Auto => Self::from(variant.resolve()),
+        }
+    }
 }
 
 impl Theme {
@@ -25,8 +106,20 @@ impl Theme {
         Self {
             variant: ThemeVariant::Dark,
             border: Style::new().fg(Color::Rgb(131, 199, 70)),
+            title: Style::reset(),
             selected: Style::new().bg(Color::Rgb(59, 59, 59)),
+            marker: Style::new()
+                .fg(Color::Rgb(131, 199, 70))
+                .add_modifier(Modifier::BOLD),
+            project_name: Style::new(),
             project_path: Style::new().fg(Color::Rgb(119, 119, 119)),
+            matched: Style::new()
+                .fg(Color::Rgb(131, 199, 70))
+                .add_modifier(Modifier::BOLD),
+            icon: Style::new().fg(Color::Rgb(219, 179, 45)),
+            preview_title: Style::reset(),
+            hint: Style::new().fg(Color::Rgb(119, 119, 119)),
+            colors: ColorSupport::TrueColor,
         }
     }
 
@@ -35,26 +128,202 @@ impl Theme {
         Self {
             variant: ThemeVariant::Light,
             border: Style::new().fg(Color::Rgb(0, 132, 0)),
+            title: Style::reset(),
             selected: Style::new().bg(Color::Rgb(205, 205, 205)),
+            marker: Style::new()
+                .fg(Color::Rgb(0, 132, 0))
+                .add_modifier(Modifier::BOLD),
+            project_name: Style::new(),
             project_path: Style::new().fg(Color::Rgb(135, 135, 135)),
+            matched: Style::new()
+                .fg(Color::Rgb(0, 132, 0))
+                .add_modifier(Modifier::BOLD),
+            icon: Style::new().fg(Color::Rgb(175, 133, 0)),
+            preview_title: Style::reset(),
+            hint: Style::new().fg(Color::Rgb(135, 135, 135)),
+            colors: ColorSupport::TrueColor,
         }
     }
+
+    /// Creates a theme without any color, relying on bold and reverse video instead.
+    pub const fn monochrome() -> Self {
+        Self {
+            variant: ThemeVariant::Dark,
+            border: Style::new(),
+            title: Style::new(),
+            selected: Style::new().add_modifier(Modifier::REVERSED),
+            marker: Style::new().add_modifier(Modifier::BOLD),
+            project_name: Style::new().add_modifier(Modifier::BOLD),
+            project_path: Style::new(),
+            matched: Style::new().add_modifier(Modifier::UNDERLINED),
+            icon: Style::new(),
+            preview_title: Style::new(),
+            hint: Style::new().add_modifier(Modifier::DIM),
+            colors: ColorSupport::None,
+        }
+    }
+
+    /// Converts the colors of the theme to the ones supported by the terminal.
+    ///
+    /// The theme is replaced by [`Theme::monochrome`] if no color is supported, since most themes
+    /// only use colors to tell the selected project apart.
+    pub fn adapt(self, colors: ColorSupport) -> Self {
+        if colors == ColorSupport::None {
+            return Self {
+                variant: self.variant,
+                ..Self::monochrome()
+            };
+        }
+
+        Self {
+            variant: self.variant,
+            border: colors.adapt(self.border),
+            title: colors.adapt(self.title),
+            selected: colors.adapt(self.selected),
+            marker: colors.adapt(self.marker),
+            project_name: colors.adapt(self.project_name),
+            project_path: colors.adapt(self.project_path),
+            matched: colors.adapt(self.matched),
+            icon: colors.adapt(self.icon),
+            preview_title: colors.adapt(self.preview_title),
+            hint: colors.adapt(self.hint),
+            colors,
+        }
+    }
+
+    /// Returns the names of the built-in themes, `dark` and `light` first.
+    pub fn builtin_names() -> impl Iterator<Item = &'static str> {
+        ["dark", "light"]
+            .into_iter()
+            .chain(BUNDLED.iter().map(|(name, _)| *name))
+    }
+
+    /// Returns the built-in theme of the given name.
+    pub fn builtin(name: &str) -> Option<Self> {
+        if let Ok(variant) = name.parse::<ThemeVariant>() {
+            return Some(variant.into());
+        }
+
+        let (_, content) = BUNDLED.iter().find(|(bundled, _)| *bundled == name)?;
+        let theme = ThemeFile::parse(content).and_then(ThemeFile::into_theme);
+
+        Some(theme.expect("bundled themes should be valid"))
+    }
+
+    /// Returns the names of the themes in the `themes` directory of the configuration.
+    ///
+    /// Themes named like a built-in one are left out, since the built-in theme is loaded instead.
+    pub fn user_names() -> Vec<String> {
+        let Some(entries) = Config::dir().and_then(|dir| fs::read_dir(dir.join("themes")).ok())
+        else {
+            return Vec::new();
+        };
+
+        let mut names: Vec<_> = entries
+            .filter_map(Result::ok)
+            .filter_map(|entry| {
+                let name = entry.file_name().into_string().ok()?;
+                name.strip_suffix(".toml").map(str::to_string)
+            })
+            .filter(|name| {
+                name.parse::<ThemeVariant>().is_err()
+                    && Self::builtin_names().all(|builtin| builtin != name)
+            })
+            .collect();
+        names.sort();
+        names
+    }
+
+    /// Loads a theme by name, or from a TOML file.
+    ///
+    /// Built-in themes are looked up first, other names are looked up as `<name>.toml` in the
+    /// `themes` directory of the configuration. Values containing a `/` or ending with `.toml` are
+    /// paths.
+    pub fn load(name: &str) -> AppResult<Self> {
+        if let Some(theme) = Self::builtin(name) {
+            return Ok(theme);
+        }
+
+        let path = if name.contains(['/', '\\']) || name.ends_with(".toml") {
+            PathBuf::from(name)
+        } else {
+            Config::dir()
+                .ok_or_else(|| format!("unknown theme '{name}'"))?
+                .join("themes")
+                .join(format!("{name}.toml"))
+        };
+
+        let content = fs::read_to_string(&path).map_err(|err| match err.kind() {
+            io::ErrorKind::NotFound => format!(
+                "unknown theme '{name}', '{}' does not exist",
+                path.display()
+            ),
+            _ => format!("cannot read '{}': {err}", path.display()),
+        })?;
+
+        ThemeFile::parse(&content)
+            .and_then(ThemeFile::into_theme)
+            .map_err(|err| format!("invalid theme '{}': {err}", path.display()).into())
+    }
 }
 
-pub fn init_theme(variant: ThemeVariant) {
-    let theme = match variant {
-        ThemeVariant::Light => Theme::enfocado_light(),
-        _ => Theme::enfocado_dark(),
-    };
+pub fn init_theme(theme: Theme) {
+    let mut current = THEME.write().expect("theme lock should not be poisoned");
 
-    THEME.set(theme).unwrap_or_else(|_| {
+    if current.is_some() {
         panic!(
             "Theme should not be set before calling {}",
             stringify!(init_theme)
-        )
-    });
+        );
+    }
+
+    *current = Some(theme);
+}
+
+/// Replaces the current theme, to preview themes while running.
+pub fn set_theme(theme: Theme) {
+    *THEME.write().expect("theme lock should not be poisoned") = Some(theme);
 }
 
 pub fn theme() -> Theme {
-    *THEME.get().expect("Theme should be set")
+    THEME
+        .read()
+        .expect("theme lock should not be poisoned")
+        .expect("Theme should be set")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn bundled_themes_are_valid() {
+        for name in Theme::builtin_names() {
+            assert!(Theme::builtin(name).is_some(), "{name}");
+        }
+        assert_eq!(
+            Theme::builtin("gruvbox-light").unwrap().variant,
+            ThemeVariant::Light
+        );
+        assert_eq!(Theme::builtin("mytheme"), None);
+    }
+
+    #[test]
+    fn adapt() {
+        let theme = Theme::enfocado_dark();
+
+        assert_eq!(theme.adapt(ColorSupport::TrueColor), theme);
+        assert_eq!(
+            theme.adapt(ColorSupport::Ansi256).selected,
+            Style::new().bg(Color::Indexed(237))
+        );
+        assert_eq!(
+            theme.adapt(ColorSupport::Ansi16).border,
+            Style::new().fg(Color::Green)
+        );
+
+        let mono = Theme::enfocado_light().adapt(ColorSupport::None);
+        assert_eq!(mono.variant, ThemeVariant::Light);
+        assert_eq!(mono.selected, Style::new().reversed());
+    }
 }
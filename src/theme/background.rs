@@ -0,0 +1,167 @@
+use std::{env, time::Duration};
+
+use super::ThemeVariant;
+
+/// Maximum time to wait for the terminal to answer the background color query.
+const QUERY_TIMEOUT: Duration = Duration::from_millis(200);
+
+/// Detects whether the terminal has a light or dark background.
+///
+/// The terminal is queried first, then `COLORFGBG` is used as set by some terminals.
+pub fn detect() -> Option<ThemeVariant> {
+    query_terminal().or_else(|| from_colorfgbg(&env::var("COLORFGBG").ok()?))
+}
+
+/// Queries the background color of the terminal with OSC 11.
+///
+/// The answer is read until the deadline only, so that keys typed afterwards are left to the
+/// interface.
+#[cfg(unix)]
+fn query_terminal() -> Option<ThemeVariant> {
+    use std::{
+        fs::File,
+        io::{Read, Write},
+        time::Instant,
+    };
+
+    use crossterm::terminal;
+
+    let mut tty = File::options()
+        .read(true)
+        .write(true)
+        .open("/dev/tty")
+        .ok()?;
+
+    terminal::enable_raw_mode().ok()?;
+
+    // The device attributes are queried too, every terminal answers them, so that reading stops
+    // even if the terminal ignores the background color query
+    let deadline = Instant::now() + QUERY_TIMEOUT;
+    let mut response = Vec::new();
+    if tty.write_all(b"\x1b]11;?\x1b\\\x1b[c").is_ok() && tty.flush().is_ok() {
+        let mut byte = [0];
+
+        while !has_device_attributes(&response)
+            && wait_readable(&tty, deadline.saturating_duration_since(Instant::now()))
+            && tty.read_exact(&mut byte).is_ok()
+        {
+            response.push(byte[0]);
+        }
+    }
+
+    let _ = terminal::disable_raw_mode();
+
+    from_osc11(&String::from_utf8_lossy(&response))
+}
+
+/// Waits until the terminal has something to read, returning `false` on timeout.
+#[cfg(unix)]
+fn wait_readable(tty: &std::fs::File, timeout: Duration) -> bool {
+    use std::os::fd::AsRawFd;
+
+    if timeout.is_zero() {
+        return false;
+    }
+
+    let mut fd = libc::pollfd {
+        fd: tty.as_raw_fd(),
+        events: libc::POLLIN,
+        revents: 0,
+    };
+    let timeout = timeout.as_millis().try_into().unwrap_or(libc::c_int::MAX);
+
+    // SAFETY: a single valid `pollfd` is given, the file descriptor being open for the whole call
+    unsafe { libc::poll(&mut fd, 1, timeout) > 0 }
+}
+
+#[cfg(not(unix))]
+fn query_terminal() -> Option<ThemeVariant> {
+    None
+}
+
+/// Returns whether the response ends with the answer to the device attributes query, like
+/// `ESC [ ? 62 ; 22 c`.
+fn has_device_attributes(response: &[u8]) -> bool {
+    response.ends_with(b"c")
+        && response
+            .windows(3)
+            .rposition(|window| window == b"\x1b[?")
+            .is_some_and(|start| {
+                response[start + 3..response.len() - 1]
+                    .iter()
+                    .all(|byte| byte.is_ascii_digit() || *byte == b';')
+            })
+}
+
+/// Parses the answer to the background color query, like `ESC ] 11 ; rgb:1e1e/1e1e/2e2e BEL`.
+fn from_osc11(response: &str) -> Option<ThemeVariant> {
+    let (_, color) = response.split_once("]11;rgb:")?;
+    let color = color.split(['\x07', '\x1b']).next()?;
+
+    let mut channels = color.split('/').map(|channel| {
+        let max = 16u32.checked_pow(channel.len() as u32)? - 1;
+        let value = u32::from_str_radix(channel, 16).ok()?;
+        (max > 0).then(|| value as f64 / max as f64)
+    });
+    let (r, g, b) = (channels.next()??, channels.next()??, channels.next()??);
+
+    Some(from_luminance(0.2126 * r + 0.7152 * g + 0.0722 * b))
+}
+
+/// Parses `COLORFGBG`, like `15;0`, the last value being the ANSI background color.
+fn from_colorfgbg(value: &str) -> Option<ThemeVariant> {
+    let background: u8 = value.rsplit(';').next()?.parse().ok()?;
+
+    match background {
+        7 | 9..=15 => Some(ThemeVariant::Light),
+        _ => Some(ThemeVariant::Dark),
+    }
+}
+
+fn from_luminance(luminance: f64) -> ThemeVariant {
+    if luminance > 0.5 {
+        ThemeVariant::Light
+    } else {
+        ThemeVariant::Dark
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn osc11() {
+        assert_eq!(
+            from_osc11("\x1b]11;rgb:1e1e/1e1e/2e2e\x07"),
+            Some(ThemeVariant::Dark)
+        );
+        assert_eq!(
+            from_osc11("\x1b]11;rgb:fdfd/f6f6/e3e3\x1b\\\x1b[?62;22c"),
+            Some(ThemeVariant::Light)
+        );
+        assert_eq!(
+            from_osc11("\x1b]11;rgb:ff/ff/ff\x07"),
+            Some(ThemeVariant::Light)
+        );
+        assert_eq!(from_osc11("\x1b[?62;22c"), None);
+        assert_eq!(from_osc11("\x1b]11;rgb:zz/00/00\x07"), None);
+    }
+
+    #[test]
+    fn device_attributes() {
+        assert!(has_device_attributes(b"\x1b]11;rgb:0/0/0\x07\x1b[?62;22c"));
+        assert!(has_device_attributes(b"\x1b[?1;2c"));
+        assert!(!has_device_attributes(b"\x1b]11;rgb:0/0/0\x07"));
+        assert!(!has_device_attributes(b"\x1b[?62;"));
+    }
+
+    #[test]
+    fn colorfgbg() {
+        assert_eq!(from_colorfgbg("15;0"), Some(ThemeVariant::Dark));
+        assert_eq!(from_colorfgbg("0;15"), Some(ThemeVariant::Light));
+        assert_eq!(from_colorfgbg("0;default;15"), Some(ThemeVariant::Light));
+        assert_eq!(from_colorfgbg("15;8"), Some(ThemeVariant::Dark));
+        assert_eq!(from_colorfgbg(""), None);
+    }
+}
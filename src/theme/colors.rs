@@ -0,0 +1,277 @@
+use std::{env, str::FromStr};
+
+use ratatui::prelude::*;
+
+/// When to use colors, from `--color`.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum ColorMode {
+    /// Detect the colors supported by the terminal.
+    #[default]
+    Auto,
+    /// Use every color of the theme.
+    Always,
+    /// Do not use any color.
+    Never,
+}
+
+impl FromStr for ColorMode {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "auto" => Ok(Self::Auto),
+            "always" => Ok(Self::Always),
+            "never" => Ok(Self::Never),
+            _ => Err(format!(
+                "invalid color mode '{s}', expected auto, always or never"
+            )),
+        }
+    }
+}
+
+/// Colors the terminal can display.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum ColorSupport {
+    /// No color at all, only modifiers like bold and reverse.
+    None,
+    /// The 16 ANSI colors.
+    Ansi16,
+    /// The 256 colors palette.
+    Ansi256,
+    /// 24-bit RGB colors.
+    #[default]
+    TrueColor,
+}
+
+/// RGB values of the 16 ANSI colors, as displayed by xterm.
+const ANSI16: [(u8, u8, u8); 16] = [
+    (0, 0, 0),
+    (205, 0, 0),
+    (0, 205, 0),
+    (205, 205, 0),
+    (0, 0, 238),
+    (205, 0, 205),
+    (0, 205, 205),
+    (229, 229, 229),
+    (127, 127, 127),
+    (255, 0, 0),
+    (0, 255, 0),
+    (255, 255, 0),
+    (92, 92, 255),
+    (255, 0, 255),
+    (0, 255, 255),
+    (255, 255, 255),
+];
+
+/// Levels of the 6x6x6 color cube of the 256 colors palette.
+const CUBE_LEVELS: [u8; 6] = [0, 95, 135, 175, 215, 255];
+
+impl ColorSupport {
+    /// Detects the colors supported by the terminal, unless forced by `mode`.
+    pub fn detect(mode: ColorMode) -> Self {
+        match mode {
+            ColorMode::Always => Self::TrueColor,
+            ColorMode::Never => Self::None,
+            ColorMode::Auto => Self::from_env(
+                env::var_os("NO_COLOR").is_some_and(|value| !value.is_empty()),
+                env::var("COLORTERM").ok().as_deref(),
+                env::var("TERM").ok().as_deref(),
+            ),
+        }
+    }
+
+    /// Returns the colors supported according to `NO_COLOR`, `COLORTERM` and `TERM`.
+    fn from_env(no_color: bool, colorterm: Option<&str>, term: Option<&str>) -> Self {
+        if no_color {
+            return Self::None;
+        }
+
+        if matches!(colorterm, Some("truecolor" | "24bit")) {
+            return Self::TrueColor;
+        }
+
+        match term {
+            Some("dumb") => Self::None,
+            Some(term) if term.contains("direct") => Self::TrueColor,
+            Some(term) if term.contains("256") => Self::Ansi256,
+            Some(_) => Self::Ansi16,
+            // Windows terminals do not set TERM but support RGB colors
+            None if cfg!(windows) => Self::TrueColor,
+            None => Self::Ansi16,
+        }
+    }
+
+    /// Converts the colors of the style to ones the terminal supports.
+    ///
+    /// Colors are removed altogether if none is supported, keeping the modifiers.
+    pub fn adapt(self, style: Style) -> Style {
+        Style {
+            fg: style.fg.and_then(|color| self.adapt_color(color)),
+            bg: style.bg.and_then(|color| self.adapt_color(color)),
+            ..style
+        }
+    }
+
+    fn adapt_color(self, color: Color) -> Option<Color> {
+        match (self, color) {
+            (Self::None, _) => None,
+            (Self::TrueColor, color) => Some(color),
+            (Self::Ansi256, Color::Rgb(r, g, b)) => Some(Color::Indexed(to_ansi256(r, g, b))),
+            (Self::Ansi16, Color::Rgb(r, g, b)) => Some(to_ansi16(r, g, b)),
+            (Self::Ansi16, Color::Indexed(index)) if index >= 16 => {
+                let (r, g, b) = indexed_rgb(index);
+                Some(to_ansi16(r, g, b))
+            }
+            (_, color) => Some(color),
+        }
+    }
+}
+
+/// Returns the closest color of the 256 colors palette, from the color cube or the grayscale ramp.
+fn to_ansi256(r: u8, g: u8, b: u8) -> u8 {
+    let level = |value: u8| {
+        (0..CUBE_LEVELS.len())
+            .min_by_key(|i| CUBE_LEVELS[*i].abs_diff(value))
+            .unwrap_or_default() as u8
+    };
+    let (ri, gi, bi) = (level(r), level(g), level(b));
+    let cube = 16 + 36 * ri + 6 * gi + bi;
+
+    let average = (r as u16 + g as u16 + b as u16) / 3;
+    let gray = 232 + ((average.saturating_sub(3)) / 10).min(23) as u8;
+
+    if distance((r, g, b), indexed_rgb(gray)) < distance((r, g, b), indexed_rgb(cube)) {
+        gray
+    } else {
+        cube
+    }
+}
+
+/// Returns the closest of the 16 ANSI colors.
+///
+/// The hue is kept rather than the closest RGB value, which would turn most muted colors into
+/// grays.
+fn to_ansi16(r: u8, g: u8, b: u8) -> Color {
+    let max = r.max(g).max(b);
+    let min = r.min(g).min(b);
+
+    if max - min < 32 {
+        return match max {
+            0..40 => Color::Black,
+            40..140 => Color::DarkGray,
+            140..220 => Color::Gray,
+            _ => Color::White,
+        };
+    }
+
+    let middle = (max as u16 + min as u16) / 2;
+    let on = |value: u8| value as u16 > middle;
+    let bright = max >= 224;
+
+    match (on(r), on(g), on(b), bright) {
+        (true, false, false, false) => Color::Red,
+        (true, false, false, true) => Color::LightRed,
+        (false, true, false, false) => Color::Green,
+        (false, true, false, true) => Color::LightGreen,
+        (true, true, false, false) => Color::Yellow,
+        (true, true, false, true) => Color::LightYellow,
+        (false, false, true, false) => Color::Blue,
+        (false, false, true, true) => Color::LightBlue,
+        (true, false, true, false) => Color::Magenta,
+        (true, false, true, true) => Color::LightMagenta,
+        (false, true, true, false) => Color::Cyan,
+        (false, true, true, true) => Color::LightCyan,
+        _ => Color::Gray,
+    }
+}
+
+/// Returns the RGB value of a color of the 256 colors palette.
+fn indexed_rgb(index: u8) -> (u8, u8, u8) {
+    match index {
+        0..=15 => ANSI16[index as usize],
+        16..=231 => {
+            let index = index - 16;
+            (
+                CUBE_LEVELS[(index / 36) as usize],
+                CUBE_LEVELS[(index / 6 % 6) as usize],
+                CUBE_LEVELS[(index % 6) as usize],
+            )
+        }
+        232..=255 => {
+            let level = 8 + 10 * (index - 232);
+            (level, level, level)
+        }
+    }
+}
+
+fn distance(a: (u8, u8, u8), b: (u8, u8, u8)) -> u32 {
+    let square = |x: u8, y: u8| (x.abs_diff(y) as u32).pow(2);
+    square(a.0, b.0) + square(a.1, b.1) + square(a.2, b.2)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn detection() {
+        let detect = ColorSupport::from_env;
+
+        assert_eq!(detect(true, Some("truecolor"), None), ColorSupport::None);
+        assert_eq!(
+            detect(false, Some("truecolor"), Some("xterm")),
+            ColorSupport::TrueColor
+        );
+        assert_eq!(detect(false, Some("24bit"), None), ColorSupport::TrueColor);
+        assert_eq!(
+            detect(false, None, Some("xterm-256color")),
+            ColorSupport::Ansi256
+        );
+        assert_eq!(
+            detect(false, None, Some("xterm-direct")),
+            ColorSupport::TrueColor
+        );
+        assert_eq!(detect(false, None, Some("linux")), ColorSupport::Ansi16);
+        assert_eq!(detect(false, None, Some("dumb")), ColorSupport::None);
+    }
+
+    #[test]
+    fn ansi256() {
+        assert_eq!(to_ansi256(0, 0, 0), 16);
+        assert_eq!(to_ansi256(255, 255, 255), 231);
+        assert_eq!(to_ansi256(255, 0, 0), 196);
+        assert_eq!(to_ansi256(59, 59, 59), 237);
+        assert_eq!(to_ansi256(131, 199, 70), 113);
+    }
+
+    #[test]
+    fn ansi16() {
+        assert_eq!(to_ansi16(131, 199, 70), Color::Green);
+        assert_eq!(to_ansi16(119, 119, 119), Color::DarkGray);
+        assert_eq!(to_ansi16(250, 250, 250), Color::White);
+        assert_eq!(to_ansi16(136, 192, 208), Color::Cyan);
+        assert_eq!(to_ansi16(255, 85, 85), Color::LightRed);
+    }
+
+    #[test]
+    fn adapt() {
+        let style = Style::new()
+            .fg(Color::Rgb(255, 0, 0))
+            .bg(Color::Indexed(196))
+            .bold();
+
+        assert_eq!(ColorSupport::TrueColor.adapt(style), style);
+        assert_eq!(
+            ColorSupport::Ansi256.adapt(style),
+            Style::new()
+                .fg(Color::Indexed(196))
+                .bg(Color::Indexed(196))
+                .bold()
+        );
+        assert_eq!(
+            ColorSupport::Ansi16.adapt(style),
+            Style::new().fg(Color::LightRed).bg(Color::LightRed).bold()
+        );
+        assert_eq!(ColorSupport::None.adapt(style), Style::new().bold());
+    }
+}
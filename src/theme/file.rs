@@ -0,0 +1,168 @@
+use std::{collections::BTreeMap, str::FromStr};
+
+use ratatui::prelude::*;
+use serde::Deserialize;
+
+use super::{Theme, ThemeVariant};
+
+/// A theme as written in a TOML file.
+///
+/// Every style is optional, missing ones are taken from the base theme.
+#[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct ThemeFile {
+    /// Built-in theme to start from, `dark` by default.
+    base: Option<String>,
+    /// Colors that can be referred to by name in the styles.
+    #[serde(default)]
+    palette: BTreeMap<String, String>,
+    #[serde(default)]
+    styles: Styles,
+}
+
+/// Style of every element of the interface.
+#[derive(Debug, Default, Deserialize)]
+#[serde(default, deny_unknown_fields, rename_all = "kebab-case")]
+struct Styles {
+    border: Option<StyleConfig>,
+    title: Option<StyleConfig>,
+    selected: Option<StyleConfig>,
+    marker: Option<StyleConfig>,
+    project_name: Option<StyleConfig>,
+    project_path: Option<StyleConfig>,
+    matched: Option<StyleConfig>,
+    icon: Option<StyleConfig>,
+    preview_title: Option<StyleConfig>,
+    hint: Option<StyleConfig>,
+}
+
+/// A style as written in a theme file.
+#[derive(Debug, Default, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+struct StyleConfig {
+    fg: Option<String>,
+    bg: Option<String>,
+    bold: bool,
+    dim: bool,
+    italic: bool,
+    underlined: bool,
+    reversed: bool,
+}
+
+impl ThemeFile {
+    /// Parses a theme from its TOML representation.
+    pub fn parse(content: &str) -> Result<Self, String> {
+        toml::from_str(content).map_err(|err| err.to_string())
+    }
+
+    /// Resolves the colors of the theme, on top of its base theme.
+    pub fn into_theme(self) -> Result<Theme, String> {
+        let base = match &self.base {
+            Some(base) => ThemeVariant::from_str(base)?,
+            None => ThemeVariant::Dark,
+        };
+        let mut theme = Theme::from(base);
+
+        let styles = [
+            (&self.styles.border, &mut theme.border),
+            (&self.styles.title, &mut theme.title),
+            (&self.styles.selected, &mut theme.selected),
+            (&self.styles.marker, &mut theme.marker),
+            (&self.styles.project_name, &mut theme.project_name),
+            (&self.styles.project_path, &mut theme.project_path),
+            (&self.styles.matched, &mut theme.matched),
+            (&self.styles.icon, &mut theme.icon),
+            (&self.styles.preview_title, &mut theme.preview_title),
+            (&self.styles.hint, &mut theme.hint),
+        ];
+
+        for (config, style) in styles {
+            if let Some(config) = config {
+                *style = self.style(config)?;
+            }
+        }
+
+        Ok(theme)
+    }
+
+    fn style(&self, config: &StyleConfig) -> Result<Style, String> {
+        let mut style = Style::new();
+
+        if let Some(fg) = &config.fg {
+            style = style.fg(self.color(fg)?);
+        }
+        if let Some(bg) = &config.bg {
+            style = style.bg(self.color(bg)?);
+        }
+
+        let modifiers = [
+            (config.bold, Modifier::BOLD),
+            (config.dim, Modifier::DIM),
+            (config.italic, Modifier::ITALIC),
+            (config.underlined, Modifier::UNDERLINED),
+            (config.reversed, Modifier::REVERSED),
+        ];
+
+        for (enabled, modifier) in modifiers {
+            if enabled {
+                style = style.add_modifier(modifier);
+            }
+        }
+
+        Ok(style)
+    }
+
+    /// Returns the color of the given name from the palette, or parses it as a color like
+    /// `#83c746`, `red` or `214`.
+    fn color(&self, name: &str) -> Result<Color, String> {
+        let color = self.palette.get(name).map_or(name, String::as_str);
+
+        Color::from_str(color).map_err(|_| format!("invalid color '{color}'"))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn empty() {
+        let theme = ThemeFile::parse("").unwrap().into_theme().unwrap();
+
+        assert_eq!(theme, Theme::enfocado_dark());
+    }
+
+    #[test]
+    fn styles() {
+        let theme = ThemeFile::parse(
+            r##"
+            base = "light"
+
+            [palette]
+            green = "#00ff00"
+
+            [styles]
+            border = { fg = "green" }
+            selected = { bg = "blue", bold = true }
+            project-path = { fg = "244" }
+            "##,
+        )
+        .unwrap()
+        .into_theme()
+        .unwrap();
+
+        assert_eq!(theme.border, Style::new().fg(Color::Rgb(0, 255, 0)));
+        assert_eq!(theme.selected, Style::new().bg(Color::Blue).bold());
+        assert_eq!(theme.project_path, Style::new().fg(Color::Indexed(244)));
+        assert_eq!(theme.hint, Theme::enfocado_light().hint);
+    }
+
+    #[test]
+    fn errors() {
+        let theme = |content| ThemeFile::parse(content).and_then(ThemeFile::into_theme);
+
+        assert!(theme("[styles]\nborder = { fg = \"nope\" }").is_err());
+        assert!(theme("[styles]\nunknown = { fg = \"red\" }").is_err());
+        assert!(theme("base = \"sepia\"").is_err());
+    }
+}
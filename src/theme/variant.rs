@@ -1,19 +1,36 @@
 use std::str::FromStr;
 
+use super::background;
+
 #[derive(Default, Clone, Copy, Debug, PartialEq, Eq)]
 pub enum ThemeVariant {
     #[default]
     Dark,
     Light,
+    /// Light or dark depending on the background color of the terminal.
+    Auto,
+}
+
+impl ThemeVariant {
+    /// Resolves [`ThemeVariant::Auto`] to the variant matching the terminal background, or dark if
+    /// it cannot be detected.
+    pub fn resolve(self) -> Self {
+        match self {
+            Self::Auto => background::detect().unwrap_or(Self::Dark),
+            variant => variant,
+        }
+    }
 }
 
 impl FromStr for ThemeVariant {
-    type Err = &'static str;
+    type Err = String;
 
     fn from_str(s: &str) -> Result<Self, Self::Err> {
         match s.to_lowercase().trim() {
             "light" => Ok(ThemeVariant::Light),
-            _ => Ok(ThemeVariant::Dark),
+            "dark" => Ok(ThemeVariant::Dark),
+            "auto" => Ok(ThemeVariant::Auto),
+            _ => Err(format!("unknown theme variant '{s}'")),
         }
     }
 }
@@ -54,10 +71,17 @@ mod tests {
             ThemeVariant::Dark
         );
         assert_eq!(ThemeVariant::from_str("DARK").unwrap(), ThemeVariant::Dark);
-        assert_eq!(ThemeVariant::from_str("").unwrap(), ThemeVariant::Dark);
-        assert_eq!(
-            ThemeVariant::from_str("whatever").unwrap(),
-            ThemeVariant::Dark
-        );
+    }
+
+    #[test]
+    fn auto() {
+        assert_eq!(ThemeVariant::from_str("auto").unwrap(), ThemeVariant::Auto);
+        assert_eq!(ThemeVariant::Light.resolve(), ThemeVariant::Light);
+    }
+
+    #[test]
+    fn unknown() {
+        assert!(ThemeVariant::from_str("").is_err());
+        assert!(ThemeVariant::from_str("whatever").is_err());
     }
 }
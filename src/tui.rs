@@ -1,7 +1,7 @@
-use std::{io, panic};
+use std::{io, panic, str::FromStr};
 
 use crossterm::{
-    event::{DisableMouseCapture, EnableMouseCapture},
+    event::{DisableBracketedPaste, DisableMouseCapture, EnableBracketedPaste, EnableMouseCapture},
     terminal::{self, EnterAlternateScreen, LeaveAlternateScreen},
 };
 use ratatui::{backend::Backend, Terminal};
@@ -21,12 +21,24 @@ pub struct Tui<B: Backend> {
     terminal: Terminal<B>,
     /// Terminal event handler.
     pub events: EventHandler,
+    /// Whether to capture the mouse.
+    ///
+    /// Capturing the mouse prevents selecting text in the terminal.
+    pub mouse_capture: bool,
+    /// Whether the terminal uses an inline viewport below the prompt instead of the alternate
+    /// screen.
+    pub inline: bool,
 }
 
 impl<B: Backend> Tui<B> {
     /// Constructs a new instance of [`Tui`].
     pub fn new(terminal: Terminal<B>, events: EventHandler) -> Self {
-        Self { terminal, events }
+        Self {
+            terminal,
+            events,
+            mouse_capture: true,
+            inline: false,
+        }
     }
 
     /// Initializes the terminal interface.
@@ -34,13 +46,21 @@ impl<B: Backend> Tui<B> {
     /// It enables the raw mode and sets terminal properties.
     pub fn init(&mut self) -> AppResult<()> {
         terminal::enable_raw_mode()?;
-        crossterm::execute!(io::stderr(), EnterAlternateScreen, EnableMouseCapture)?;
+        if !self.inline {
+            crossterm::execute!(io::stderr(), EnterAlternateScreen)?;
+        }
+        if self.mouse_capture {
+            crossterm::execute!(io::stderr(), EnableMouseCapture)?;
+        }
+        // Not supported by legacy Windows consoles, pasted text is then received as key presses
+        let _ = crossterm::execute!(io::stderr(), EnableBracketedPaste);
 
         // Define a custom panic hook to reset the terminal properties.
         // This way, you won't have your terminal messed up if an unexpected error happens.
         let panic_hook = panic::take_hook();
+        let inline = self.inline;
         panic::set_hook(Box::new(move |panic| {
-            Self::reset().expect("failed to reset the terminal");
+            Self::reset(inline).expect("failed to reset the terminal");
             panic_hook(panic);
         }));
 
@@ -63,9 +83,13 @@ impl<B: Backend> Tui<B> {
     ///
     /// This function is also used for the panic hook to revert
     /// the terminal properties if unexpected errors occur.
-    fn reset() -> AppResult<()> {
+    fn reset(inline: bool) -> AppResult<()> {
         terminal::disable_raw_mode()?;
-        crossterm::execute!(io::stderr(), LeaveAlternateScreen, DisableMouseCapture)?;
+        let _ = crossterm::execute!(io::stderr(), DisableBracketedPaste);
+        crossterm::execute!(io::stderr(), DisableMouseCapture)?;
+        if !inline {
+            crossterm::execute!(io::stderr(), LeaveAlternateScreen)?;
+        }
         Ok(())
     }
 
@@ -73,8 +97,88 @@ impl<B: Backend> Tui<B> {
     ///
     /// It disables the raw mode and reverts back the terminal properties.
     pub fn exit(&mut self) -> AppResult<()> {
-        Self::reset()?;
+        if self.inline {
+            // Leave the prompt where it was, without the interface below it
+            self.terminal.clear()?;
+        }
+        Self::reset(self.inline)?;
         self.terminal.show_cursor()?;
         Ok(())
     }
 }
+
+/// Height of the interface when rendered inline, below the prompt.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Height {
+    /// Number of lines.
+    Lines(u16),
+    /// Percentage of the terminal height.
+    Percent(u16),
+}
+
+impl Default for Height {
+    fn default() -> Self {
+        Self::Percent(40)
+    }
+}
+
+impl Height {
+    /// Minimum number of lines, enough for the search bar and a few projects.
+    const MIN_LINES: u16 = 8;
+
+    /// Returns the number of lines to use in a terminal of the given height.
+    pub fn lines(self, terminal_height: u16) -> u16 {
+        let lines = match self {
+            Self::Lines(lines) => lines,
+            Self::Percent(percent) => (terminal_height as u32 * percent as u32 / 100) as u16,
+        };
+
+        lines.max(Self::MIN_LINES).min(terminal_height)
+    }
+}
+
+impl FromStr for Height {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let invalid =
+            || format!("invalid height '{s}', expected lines like '20' or a percentage like '40%'");
+
+        match s.strip_suffix('%') {
+            Some(percent) => percent
+                .parse()
+                .ok()
+                .filter(|percent| (1..=100).contains(percent))
+                .map(Self::Percent)
+                .ok_or_else(invalid),
+            None => s
+                .parse()
+                .ok()
+                .filter(|lines| *lines > 0)
+                .map(Self::Lines)
+                .ok_or_else(invalid),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_height() {
+        assert_eq!("40%".parse(), Ok(Height::Percent(40)));
+        assert_eq!("20".parse(), Ok(Height::Lines(20)));
+        assert!("0".parse::<Height>().is_err());
+        assert!("101%".parse::<Height>().is_err());
+        assert!("half".parse::<Height>().is_err());
+    }
+
+    #[test]
+    fn height_lines() {
+        assert_eq!(Height::Percent(40).lines(50), 20);
+        assert_eq!(Height::Percent(40).lines(10), 8);
+        assert_eq!(Height::Lines(20).lines(50), 20);
+        assert_eq!(Height::Lines(20).lines(6), 6);
+    }
+}
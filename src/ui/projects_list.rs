@@ -1,3 +1,5 @@
+use std::path::Path;
+
 use ratatui::{
     prelude::*,
     widgets::{block::Title, *},
@@ -5,8 +7,33 @@ use ratatui::{
 
 use crate::{app::App, theme::theme};
 
+/// Frames of the spinner displayed while projects are being discovered.
+const SPINNER: [&str; 10] = ["⠋", "⠙", "⠹", "⠸", "⠼", "⠴", "⠦", "⠧", "⠇", "⠏"];
+
 pub fn render(area: Rect, buf: &mut Buffer, app: &mut App) {
-    let projects = List::new(app.filtered_projects.clone())
+    let items = app.filtered_projects.iter().map(|project| {
+        let mut line = Line::from(project);
+        line.spans = line
+            .spans
+            .into_iter()
+            .flat_map(|span| highlight(span, app.search(), theme().matched))
+            .collect();
+
+        let marker = if app.is_marked(project) { "+" } else { " " };
+        let pin = if app.is_pinned(project) { "*" } else { " " };
+        line.spans.insert(0, Span::styled(pin, theme().icon));
+        line.spans.insert(0, Span::styled(marker, theme().marker));
+
+        // Nested projects are prefixed by the name of their parent, dimmed
+        if let Some(parent) = app.parent(project).and_then(Path::file_name) {
+            let parent = format!("{}/", parent.to_string_lossy());
+            line.spans
+                .insert(2, Span::styled(parent, theme().project_path));
+        }
+        ListItem::new(line)
+    });
+
+    let projects = List::new(items)
         .block(
             Block::bordered()
                 .border_type(BorderType::Rounded)
@@ -14,13 +41,133 @@ pub fn render(area: Rect, buf: &mut Buffer, app: &mut App) {
                 .title(
                     Title::default()
                         .alignment(Alignment::Center)
-                        .content(format!(" Results in {}ms ", app.start_time.as_millis()).reset()),
+                        .content(Span::styled(
+                            title(app, area.width.saturating_sub(2)),
+                            theme().title,
+                        )),
                 ),
         )
         .highlight_symbol(" ")
         .highlight_spacing(HighlightSpacing::Always)
         .highlight_style(theme().selected)
-        .direction(ListDirection::BottomToTop);
+        .direction(app.layout.order.into());
 
     StatefulWidget::render(projects, area, buf, &mut app.list_state);
 }
+
+/// Splits a span to patch the characters matching the search with the `matched` style.
+///
+/// Like the sorter, the search is matched by pairs of characters, ignoring the case.
+fn highlight<'a>(span: Span<'a>, search: &str, matched_style: Style) -> Vec<Span<'a>> {
+    let lower = |c: char| c.to_lowercase().next().unwrap_or(c);
+    let search: Vec<char> = search.chars().map(lower).collect();
+    let chars: Vec<(usize, char)> = span
+        .content
+        .char_indices()
+        .map(|(i, c)| (i, lower(c)))
+        .collect();
+
+    let len = search.len().min(2);
+    let mut matched = vec![false; chars.len()];
+    if len > 0 {
+        for ngram in search.windows(len) {
+            for (start, window) in chars.windows(len).enumerate() {
+                if window.iter().map(|(_, c)| c).eq(ngram) {
+                    matched[start..start + len].fill(true);
+                }
+            }
+        }
+    }
+
+    if !matched.contains(&true) {
+        return vec![span];
+    }
+
+    // Group the characters in runs of matched and unmatched ones
+    let mut spans = Vec::new();
+    let mut start = 0;
+    for i in 1..=chars.len() {
+        if i == chars.len() || matched[i] != matched[start] {
+            let end = chars.get(i).map_or(span.content.len(), |(byte, _)| *byte);
+            let content = span.content[chars[start].0..end].to_string();
+            let style = if matched[start] {
+                span.style.patch(matched_style)
+            } else {
+                span.style
+            };
+            spans.push(Span::styled(content, style));
+            start = i;
+        }
+    }
+    spans
+}
+
+/// Returns the longest title fitting in `width`, dropping the least useful parts first.
+fn title(app: &App, width: u16) -> String {
+    let count = format!("{}/{}", app.filtered_projects.len(), app.projects.len());
+    let duration = format!("{}ms", app.scan_duration().as_millis());
+
+    let titles = if app.is_scanning() {
+        let frame = SPINNER[(app.scan_duration().as_millis() / 100) as usize % SPINNER.len()];
+        [
+            format!(" {frame} {count} scanning for {duration} "),
+            format!(" {frame} {count} scanning "),
+            format!(" {frame} {count} "),
+            format!(" {frame} "),
+        ]
+    } else {
+        [
+            format!(" {count} results in {duration} "),
+            format!(" {count} in {duration} "),
+            format!(" {count} "),
+            String::new(),
+        ]
+    };
+
+    titles
+        .into_iter()
+        .find(|title| title.chars().count() <= width as usize)
+        .unwrap_or_default()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::project::Project;
+
+    #[test]
+    fn title_truncates() {
+        let mut app =
+            App::new(["alpha", "beta"].map(|name| Project::test(format!("/home/{name}"))));
+        app.start_time = std::time::Duration::from_millis(42);
+
+        assert_eq!(title(&app, 40), " 2/2 results in 42ms ");
+        assert_eq!(title(&app, 13), " 2/2 in 42ms ");
+        assert_eq!(title(&app, 5), " 2/2 ");
+        assert_eq!(title(&app, 4), "");
+    }
+
+    #[test]
+    fn highlight_matches() {
+        let highlighted = |content: &'static str, search| {
+            highlight(Span::raw(content), search, Style::new().bold())
+                .into_iter()
+                .map(|span| (span.content.into_owned(), span.style == Style::new().bold()))
+                .collect::<Vec<_>>()
+        };
+
+        assert_eq!(
+            highlighted("Crate-fp", "raF"),
+            [
+                ("C".into(), false),
+                ("ra".into(), true),
+                ("te-fp".into(), false)
+            ]
+        );
+        assert_eq!(
+            highlighted("Été", "ét"),
+            [("Ét".into(), true), ("é".into(), false)]
+        );
+        assert_eq!(highlighted("crate", ""), [("crate".into(), false)]);
+    }
+}
@@ -1,5 +1,9 @@
+mod action_menu;
+mod help;
 mod preview;
 mod searchbar;
 
+pub use action_menu::ActionMenu;
+pub use help::Help;
 pub use preview::Preview;
 pub use searchbar::Searchbar;
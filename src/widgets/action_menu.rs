@@ -0,0 +1,147 @@
+use ratatui::{
+    prelude::*,
+    widgets::{block::Title, *},
+};
+
+use crate::{action::Action, sorter::Sorter, theme::theme, widgets::Searchbar};
+
+/// Popup listing the actions available for the selected project.
+pub struct ActionMenu {
+    visible: bool,
+    searchbar: Searchbar,
+    /// Actions available for the selected project
+    actions: Vec<Action>,
+    /// Actions matching the search
+    filtered_actions: Vec<Action>,
+    list_state: ListState,
+}
+
+impl Default for ActionMenu {
+    fn default() -> Self {
+        Self {
+            visible: false,
+            searchbar: Searchbar::new(" Run action "),
+            actions: Vec::new(),
+            filtered_actions: Vec::new(),
+            list_state: ListState::default().with_selected(Some(0)),
+        }
+    }
+}
+
+impl ActionMenu {
+    /// Opens the menu with the given actions, resetting the search.
+    pub fn open(&mut self, actions: Vec<Action>) {
+        self.searchbar.clear();
+        self.filtered_actions.clone_from(&actions);
+        self.actions = actions;
+        self.list_state.select(Some(0));
+        self.visible = true;
+    }
+
+    pub fn close(&mut self) {
+        self.visible = false;
+    }
+
+    pub fn is_visible(&self) -> bool {
+        self.visible
+    }
+
+    /// Returns the number of lines needed to display every action, including borders.
+    pub fn height(&self) -> u16 {
+        self.actions.len() as u16 + 2 + 3
+    }
+
+    /// Returns the currently highlighted action
+    pub fn selected(&self) -> Option<&Action> {
+        self.filtered_actions
+            .get(self.list_state.selected().unwrap_or(0))
+    }
+
+    /// Go up one in the list, wrapping around.
+    pub fn up(&mut self) {
+        let len = self.filtered_actions.len().max(1);
+        let selected = self.list_state.selected().unwrap_or(0);
+        self.list_state.select(Some((selected + 1) % len));
+    }
+
+    /// Go down one in the list, wrapping around.
+    pub fn down(&mut self) {
+        let len = self.filtered_actions.len().max(1);
+        let selected = self.list_state.selected().unwrap_or(0);
+        self.list_state.select(Some((selected + len - 1) % len));
+    }
+
+    /// Applies an edit to the search, filtering the actions again if the search changed.
+    pub fn edit_search(&mut self, edit: impl FnOnce(&mut Searchbar), sorter: &mut Sorter) {
+        let previous = self.searchbar.content().to_string();
+        edit(&mut self.searchbar);
+
+        if self.searchbar.content() != previous {
+            self.filter(sorter);
+        }
+    }
+
+    /// Filters the actions according to the search.
+    fn filter(&mut self, sorter: &mut Sorter) {
+        if self.searchbar.content().is_empty() {
+            self.filtered_actions.clone_from(&self.actions);
+        } else {
+            self.filtered_actions = sorter.sort(&self.actions, self.searchbar.content());
+        }
+        self.list_state.select(Some(0));
+    }
+}
+
+impl Widget for &mut ActionMenu {
+    fn render(self, area: Rect, buf: &mut Buffer)
+    where
+        Self: Sized,
+    {
+        Clear.render(area, buf);
+
+        let [list_chunk, searchbar_chunk] = Layout::new(
+            Direction::Vertical,
+            [Constraint::Min(3), Constraint::Length(3)],
+        )
+        .areas(area);
+
+        let name_width = self
+            .actions
+            .iter()
+            .map(|action| action.name.len())
+            .max()
+            .unwrap_or_default();
+
+        let items = self.filtered_actions.iter().map(|action| {
+            let key = action
+                .key
+                .map(|key| format!(" <{key}>"))
+                .unwrap_or_default();
+
+            ListItem::new(Line::from(vec![
+                format!("{:name_width$} ", action.name).into(),
+                Span::styled(action.summary().to_string(), theme().hint),
+                Span::styled(key, theme().hint),
+            ]))
+        });
+
+        let list = List::new(items)
+            .block(
+                Block::bordered()
+                    .border_type(BorderType::Rounded)
+                    .border_style(theme().border)
+                    .title(
+                        Title::default()
+                            .alignment(Alignment::Center)
+                            .content(Span::styled(" Actions ", theme().title)),
+                    ),
+            )
+            .highlight_symbol(" ")
+            .highlight_spacing(HighlightSpacing::Always)
+            .highlight_style(theme().selected)
+            .direction(ListDirection::BottomToTop);
+
+        StatefulWidget::render(list, list_chunk, buf, &mut self.list_state);
+        self.searchbar.render(searchbar_chunk, buf);
+    }
+}
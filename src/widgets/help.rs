@@ -0,0 +1,204 @@
+use ratatui::{
+    prelude::*,
+    widgets::{block::Title, *},
+};
+
+use crate::{
+    action::Action,
+    keymap::{Command, Keymap, NormalCommand, NORMAL_MODE},
+    theme::theme,
+};
+
+/// Popup listing the key bindings.
+#[derive(Default)]
+pub struct Help {
+    visible: bool,
+    /// Keys, name and description of each command
+    entries: Vec<[String; 3]>,
+    /// Index of the first entry displayed
+    scroll: usize,
+}
+
+impl Help {
+    /// Opens the help with the commands of the given keymap, followed by the actions and the keys
+    /// of the normal mode if modal editing is enabled.
+    pub fn open(&mut self, keymap: &Keymap, actions: &[Action], vim: bool) {
+        let commands = Command::BUILTINS
+            .into_iter()
+            .map(|command| (command.description().to_string(), command))
+            .chain(actions.iter().map(|action| {
+                (
+                    action.summary().to_string(),
+                    Command::Action(action.name.clone()),
+                )
+            }));
+
+        self.entries = commands
+            .map(|(description, command)| {
+                let keys: Vec<_> = keymap
+                    .keys_for(&command)
+                    .iter()
+                    .map(ToString::to_string)
+                    .collect();
+                [keys.join(", "), command.to_string(), description]
+            })
+            .collect();
+
+        if vim {
+            self.entries.push(Default::default());
+            self.entries
+                .push([String::new(), "normal mode".to_string(), String::new()]);
+            self.entries.extend(normal_mode_entries());
+        }
+        self.scroll = 0;
+        self.visible = true;
+    }
+
+    pub fn close(&mut self) {
+        self.visible = false;
+    }
+
+    pub fn is_visible(&self) -> bool {
+        self.visible
+    }
+
+    /// Returns the number of lines needed to display every command, including borders.
+    pub fn height(&self) -> u16 {
+        self.entries.len() as u16 + 2
+    }
+
+    /// Scrolls by the given number of lines, negative values scroll up.
+    ///
+    /// The scroll is bounded when rendering, once the number of visible lines is known.
+    pub fn scroll_by(&mut self, lines: isize) {
+        self.scroll = self.scroll.saturating_add_signed(lines);
+    }
+}
+
+/// Returns the keys, name and description of each normal mode command.
+fn normal_mode_entries() -> Vec<[String; 3]> {
+    let mut commands: Vec<(&NormalCommand, Vec<String>)> = Vec::new();
+
+    for (keys, command) in &NORMAL_MODE {
+        let sequence = keys.iter().map(ToString::to_string).collect();
+        match commands.iter_mut().find(|(bound, _)| *bound == command) {
+            Some((_, sequences)) => sequences.push(sequence),
+            None => commands.push((command, vec![sequence])),
+        }
+    }
+
+    commands
+        .into_iter()
+        .map(|(command, sequences)| {
+            [
+                sequences.join(", "),
+                command.to_string(),
+                command.description().to_string(),
+            ]
+        })
+        .collect()
+}
+
+impl Widget for &mut Help {
+    fn render(self, area: Rect, buf: &mut Buffer)
+    where
+        Self: Sized,
+    {
+        Clear.render(area, buf);
+
+        let width = |column: usize| {
+            self.entries
+                .iter()
+                .map(|entry| entry[column].chars().count())
+                .max()
+                .unwrap_or_default()
+        };
+        let (keys_width, name_width) = (width(0), width(1));
+
+        self.scroll = self.scroll.min(
+            self.entries
+                .len()
+                .saturating_sub(area.height.saturating_sub(2) as usize),
+        );
+
+        let lines: Vec<_> = self
+            .entries
+            .iter()
+            .map(|[keys, name, description]| {
+                Line::from(vec![
+                    format!(" {keys:keys_width$}  ").into(),
+                    format!("{name:name_width$}  ").into(),
+                    Span::styled(description.clone(), theme().hint),
+                ])
+            })
+            .collect();
+
+        Paragraph::new(lines)
+            .scroll((self.scroll as u16, 0))
+            .block(
+                Block::bordered()
+                    .border_type(BorderType::Rounded)
+                    .border_style(theme().border)
+                    .title(
+                        Title::default()
+                            .alignment(Alignment::Center)
+                            .content(Span::styled(" Key bindings ", theme().title)),
+                    ),
+            )
+            .render(area, buf);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn lists_commands_and_actions() {
+        let mut help = Help::default();
+
+        help.open(&Keymap::default(), &Action::builtins(), false);
+
+        assert!(help.is_visible());
+        assert_eq!(
+            help.entries[0],
+            [
+                "ctrl-c, esc".to_string(),
+                "cancel".to_string(),
+                Command::Cancel.description().to_string(),
+            ]
+        );
+        assert_eq!(
+            help.entries.last().unwrap()[1..],
+            [
+                "action:reveal".to_string(),
+                "Reveal in file manager".to_string()
+            ]
+        );
+        assert_eq!(
+            help.height() as usize,
+            Command::BUILTINS.len() + Action::builtins().len() + 2
+        );
+    }
+
+    #[test]
+    fn lists_normal_mode_keys() {
+        let mut help = Help::default();
+
+        help.open(&Keymap::default(), &[], true);
+
+        assert!(help.entries.contains(&[
+            "gg".to_string(),
+            "first".to_string(),
+            Command::First.description().to_string(),
+        ]));
+        assert_eq!(
+            help.entries.last().unwrap(),
+            &[
+                "/, i, a".to_string(),
+                "insert".to_string(),
+                "go back to insert mode".to_string(),
+            ]
+        );
+    }
+}
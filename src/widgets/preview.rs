@@ -14,11 +14,21 @@ pub struct Preview {
     /// Note that even if `true`, preview might be hidden if there is not enough space.
     visible: bool,
     selected: Option<Project>,
+    /// Index of the first file displayed
+    scroll: usize,
 }
 
 impl Preview {
     pub fn select(&mut self, project: Option<Project>) {
-        self.selected = project
+        self.selected = project;
+        self.scroll = 0;
+    }
+
+    /// Scrolls the file list by the given number of lines, negative values scroll up.
+    ///
+    /// The scroll is bounded when rendering, once the number of visible lines is known.
+    pub fn scroll_by(&mut self, lines: isize) {
+        self.scroll = self.scroll.saturating_add_signed(lines);
     }
 
     pub fn toggle(&mut self) {
@@ -35,11 +45,12 @@ impl Default for Preview {
         Self {
             visible: true,
             selected: None,
+            scroll: 0,
         }
     }
 }
 
-impl Widget for &Preview {
+impl Widget for &mut Preview {
     fn render(self, area: Rect, buf: &mut Buffer)
     where
         Self: Sized,
@@ -52,7 +63,7 @@ impl Widget for &Preview {
                     if let Some(style) = LS_COLORS.style_for_path(e.path()) {
                         ListItem::new(Span::styled(
                             e.file_name().to_str().unwrap().to_owned(),
-                            style.to_crossterm_style(),
+                            theme().colors.adapt(style.to_crossterm_style().into()),
                         ))
                     } else {
                         ListItem::new(e.file_name().to_str().unwrap().to_owned())
@@ -63,6 +74,9 @@ impl Widget for &Preview {
             vec![]
         };
 
+        let height = area.height.saturating_sub(2) as usize;
+        self.scroll = self.scroll.min(files.len().saturating_sub(height));
+
         let preview = List::new(files).block(
             Block::bordered()
                 .border_type(BorderType::Rounded)
@@ -70,10 +84,11 @@ impl Widget for &Preview {
                 .title(
                     Title::default()
                         .alignment(Alignment::Center)
-                        .content(" Files ".reset()),
+                        .content(Span::styled(" Files ", theme().preview_title)),
                 ),
         );
 
-        Widget::render(preview, area, buf);
+        let mut state = ListState::default().with_offset(self.scroll);
+        StatefulWidget::render(preview, area, buf, &mut state);
     }
 }
@@ -2,30 +2,157 @@ use ratatui::{
     prelude::*,
     widgets::{block::Title, *},
 };
+use unicode_width::UnicodeWidthStr;
 
 use crate::theme::theme;
 
-#[derive(Default)]
 pub struct Searchbar {
     /// Search string
     search: String,
+    /// Position of the cursor, as a byte index in `search`
+    cursor: usize,
+    /// Title of the search box
+    title: &'static str,
+    /// Short text displayed on the left of the border, like the editing mode
+    indicator: Option<String>,
+    /// Short text displayed on the right of the border, like how to show the help
+    hint: Option<String>,
+}
+
+impl Default for Searchbar {
+    fn default() -> Self {
+        Self::new(" Find projects ")
+    }
 }
 
 impl Searchbar {
+    pub fn new(title: &'static str) -> Self {
+        Self {
+            search: String::new(),
+            cursor: 0,
+            title,
+            indicator: None,
+            hint: None,
+        }
+    }
+
+    pub fn set_hint(&mut self, hint: Option<String>) {
+        self.hint = hint;
+    }
+
+    pub fn set_indicator(&mut self, indicator: Option<String>) {
+        self.indicator = indicator;
+    }
+
     pub fn content(&self) -> &str {
         &self.search
     }
 
+    /// Inserts a character at the cursor.
     pub fn push(&mut self, c: char) {
-        self.search.push(c);
+        self.search.insert(self.cursor, c);
+        self.cursor += c.len_utf8();
+    }
+
+    /// Inserts a string at the cursor.
+    pub fn push_str(&mut self, s: &str) {
+        self.search.insert_str(self.cursor, s);
+        self.cursor += s.len();
     }
 
+    /// Deletes the character before the cursor.
     pub fn pop(&mut self) {
-        self.search.pop();
+        if let Some(start) = self.previous_boundary() {
+            self.search.drain(start..self.cursor);
+            self.cursor = start;
+        }
+    }
+
+    /// Deletes the character under the cursor.
+    pub fn delete(&mut self) {
+        if let Some(end) = self.next_boundary() {
+            self.search.drain(self.cursor..end);
+        }
     }
 
     pub fn clear(&mut self) {
         self.search.clear();
+        self.cursor = 0;
+    }
+
+    /// Moves the cursor one character left.
+    pub fn left(&mut self) {
+        self.cursor = self.previous_boundary().unwrap_or(self.cursor);
+    }
+
+    /// Moves the cursor one character right.
+    pub fn right(&mut self) {
+        self.cursor = self.next_boundary().unwrap_or(self.cursor);
+    }
+
+    /// Moves the cursor to the start of the search.
+    pub fn home(&mut self) {
+        self.cursor = 0;
+    }
+
+    /// Moves the cursor to the end of the search.
+    pub fn end(&mut self) {
+        self.cursor = self.search.len();
+    }
+
+    /// Moves the cursor to the start of the current or previous word.
+    pub fn word_left(&mut self) {
+        self.cursor = self.previous_word_start();
+    }
+
+    /// Moves the cursor to the end of the current or next word.
+    pub fn word_right(&mut self) {
+        let after = &self.search[self.cursor..];
+        let word = after.find(char::is_alphanumeric).unwrap_or(after.len());
+        let end = after[word..]
+            .find(|c: char| !c.is_alphanumeric())
+            .map_or(after.len(), |end| word + end);
+
+        self.cursor += end;
+    }
+
+    /// Deletes from the start of the current or previous word to the cursor.
+    pub fn delete_word(&mut self) {
+        let start = self.previous_word_start();
+        self.search.drain(start..self.cursor);
+        self.cursor = start;
+    }
+
+    /// Returns the column of the cursor, taking wide characters into account.
+    fn cursor_column(&self) -> u16 {
+        self.search[..self.cursor].width() as u16
+    }
+
+    fn previous_boundary(&self) -> Option<usize> {
+        self.search[..self.cursor]
+            .char_indices()
+            .next_back()
+            .map(|(i, _)| i)
+    }
+
+    fn next_boundary(&self) -> Option<usize> {
+        self.search[self.cursor..]
+            .chars()
+            .next()
+            .map(|c| self.cursor + c.len_utf8())
+    }
+
+    fn previous_word_start(&self) -> usize {
+        let before = &self.search[..self.cursor];
+        let word_end = before.rfind(char::is_alphanumeric).map_or(0, |i| {
+            i + before[i..].chars().next().map_or(0, char::len_utf8)
+        });
+
+        before[..word_end]
+            .char_indices()
+            .rev()
+            .find(|(_, c)| !c.is_alphanumeric())
+            .map_or(0, |(i, c)| i + c.len_utf8())
     }
 }
 
@@ -34,21 +161,184 @@ impl Widget for &Searchbar {
     where
         Self: Sized,
     {
-        let searchbox = Paragraph::new(self.search.clone()).block(
-            Block::bordered()
-                .border_type(BorderType::Rounded)
-                .border_style(theme().border)
-                .title(
-                    Title::default()
-                        .alignment(Alignment::Center)
-                        .content(" Find projects ".reset()),
-                ),
-        );
+        // Scroll horizontally so that the cursor is always visible
+        let inner_width = area.width.saturating_sub(2);
+        let cursor = self.cursor_column();
+        let scroll = (cursor + 1).saturating_sub(inner_width);
+
+        let mut block = Block::bordered()
+            .border_type(BorderType::Rounded)
+            .border_style(theme().border)
+            .title(
+                Title::default()
+                    .alignment(Alignment::Center)
+                    .content(Span::styled(self.title, theme().title)),
+            );
+
+        if let Some(indicator) = &self.indicator {
+            block = block.title(
+                Title::default()
+                    .alignment(Alignment::Left)
+                    .content(Span::styled(format!(" {indicator} "), theme().title.bold())),
+            );
+        }
+
+        if let Some(hint) = &self.hint {
+            block = block.title(
+                Title::default()
+                    .alignment(Alignment::Right)
+                    .content(Span::styled(format!(" {hint} "), theme().hint)),
+            );
+        }
+
+        let searchbox = Paragraph::new(self.search.clone())
+            .scroll((0, scroll))
+            .block(block);
 
         searchbox.render(area, buf);
 
-        let x = area.x + 1 + self.search.len() as u16;
+        if inner_width == 0 || area.height < 3 {
+            return;
+        }
+
+        let x = area.x + 1 + cursor - scroll;
         let y = area.y + 1;
-        buf.set_string(x, y, "█", Style::reset());
+        if self.cursor == self.search.len() {
+            buf.set_string(x, y, "█", Style::reset());
+        } else {
+            buf.get_mut(x, y)
+                .set_style(Style::reset().add_modifier(Modifier::REVERSED));
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn searchbar(content: &str, cursor: usize) -> Searchbar {
+        Searchbar {
+            search: content.to_string(),
+            cursor,
+            title: "",
+            indicator: None,
+            hint: None,
+        }
+    }
+
+    #[test]
+    fn push_at_cursor() {
+        let mut searchbar = searchbar("fp", 1);
+
+        searchbar.push('é');
+
+        assert_eq!(searchbar.content(), "fép");
+        assert_eq!(searchbar.cursor, 3);
+    }
+
+    #[test]
+    fn push_str_at_cursor() {
+        let mut searchbar = searchbar("fp", 1);
+
+        searchbar.push_str("ind ");
+
+        assert_eq!(searchbar.content(), "find p");
+        assert_eq!(searchbar.cursor, 5);
+    }
+
+    #[test]
+    fn pop_and_delete() {
+        let mut searchbar = searchbar("aéb", 3);
+
+        searchbar.pop();
+        assert_eq!(searchbar.content(), "ab");
+        assert_eq!(searchbar.cursor, 1);
+
+        searchbar.delete();
+        assert_eq!(searchbar.content(), "a");
+        assert_eq!(searchbar.cursor, 1);
+
+        searchbar.delete();
+        assert_eq!(searchbar.content(), "a");
+
+        searchbar.home();
+        searchbar.pop();
+        assert_eq!(searchbar.content(), "a");
+    }
+
+    #[test]
+    fn move_by_char() {
+        let mut searchbar = searchbar("日本", 0);
+
+        searchbar.right();
+        assert_eq!(searchbar.cursor, 3);
+        searchbar.right();
+        assert_eq!(searchbar.cursor, 6);
+        searchbar.right();
+        assert_eq!(searchbar.cursor, 6);
+        searchbar.left();
+        assert_eq!(searchbar.cursor, 3);
+        searchbar.home();
+        assert_eq!(searchbar.cursor, 0);
+        searchbar.left();
+        assert_eq!(searchbar.cursor, 0);
+        searchbar.end();
+        assert_eq!(searchbar.cursor, 6);
+    }
+
+    #[test]
+    fn move_by_word() {
+        let mut searchbar = searchbar("foo/bar baz", 11);
+
+        searchbar.word_left();
+        assert_eq!(searchbar.cursor, 8);
+        searchbar.word_left();
+        assert_eq!(searchbar.cursor, 4);
+        searchbar.word_left();
+        assert_eq!(searchbar.cursor, 0);
+
+        searchbar.word_right();
+        assert_eq!(searchbar.cursor, 3);
+        searchbar.word_right();
+        assert_eq!(searchbar.cursor, 7);
+        searchbar.word_right();
+        assert_eq!(searchbar.cursor, 11);
+        searchbar.word_right();
+        assert_eq!(searchbar.cursor, 11);
+    }
+
+    #[test]
+    fn delete_word() {
+        let mut searchbar = searchbar("foo/bar/", 8);
+
+        searchbar.delete_word();
+        assert_eq!(searchbar.content(), "foo/");
+        searchbar.delete_word();
+        assert_eq!(searchbar.content(), "");
+        searchbar.delete_word();
+        assert_eq!(searchbar.content(), "");
+    }
+
+    #[test]
+    fn delete_word_in_middle() {
+        let mut searchbar = searchbar("foo bar baz", 6);
+
+        searchbar.delete_word();
+
+        assert_eq!(searchbar.content(), "foo r baz");
+        assert_eq!(searchbar.cursor, 4);
+    }
+
+    #[test]
+    fn wide_characters_column() {
+        let mut searchbar = searchbar("a日本", 0);
+
+        assert_eq!(searchbar.cursor_column(), 0);
+        searchbar.right();
+        assert_eq!(searchbar.cursor_column(), 1);
+        searchbar.right();
+        assert_eq!(searchbar.cursor_column(), 3);
+        searchbar.end();
+        assert_eq!(searchbar.cursor_column(), 5);
     }
 }